@@ -3,8 +3,14 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use mirror_protocol::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, MigrateMsg, PriceResponse,
-    PricesResponse, QueryMsg,
+    AllEffectivePricesResponse, AssetExistsResponse, AssetTokensResponse,
+    BatchPortfolioValueResponse, BigMoversResponse, ConfigResponse, CurrentPriceSourceResponse,
+    ExecuteMsg, ExecutionPriceResponse, FeederResponse, FeedersResponse, FullPriceResponse,
+    HealthScoreResponse, InstantiateMsg, MarketIndexResponse, MigrateMsg, MostStaleResponse,
+    OverviewResponse, PathPriceResponse, PauseStatusResponse, PriceHookMsg, PriceResponse,
+    PriceRootResponse, PricesMapResponse, PricesResponse, QueryMsg, QuotedPriceResponse,
+    RawConfigResponse, SafePriceResponse, SnapshotResponse, StaleAtResponse,
+    UpdateTimeDiffResponse,
 };
 
 fn main() {
@@ -17,8 +23,32 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(PriceHookMsg), &out_dir);
     export_schema(&schema_for!(FeederResponse), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(RawConfigResponse), &out_dir);
+    export_schema(&schema_for!(PauseStatusResponse), &out_dir);
+    export_schema(&schema_for!(StaleAtResponse), &out_dir);
+    export_schema(&schema_for!(CurrentPriceSourceResponse), &out_dir);
+    export_schema(&schema_for!(SnapshotResponse), &out_dir);
+    export_schema(&schema_for!(AllEffectivePricesResponse), &out_dir);
+    export_schema(&schema_for!(BatchPortfolioValueResponse), &out_dir);
     export_schema(&schema_for!(PriceResponse), &out_dir);
     export_schema(&schema_for!(PricesResponse), &out_dir);
+    export_schema(&schema_for!(PricesMapResponse), &out_dir);
+    export_schema(&schema_for!(FeedersResponse), &out_dir);
+    export_schema(&schema_for!(AssetExistsResponse), &out_dir);
+    export_schema(&schema_for!(OverviewResponse), &out_dir);
+    export_schema(&schema_for!(AssetTokensResponse), &out_dir);
+    export_schema(&schema_for!(MostStaleResponse), &out_dir);
+    export_schema(&schema_for!(SafePriceResponse), &out_dir);
+    export_schema(&schema_for!(UpdateTimeDiffResponse), &out_dir);
+    export_schema(&schema_for!(MarketIndexResponse), &out_dir);
+    export_schema(&schema_for!(PathPriceResponse), &out_dir);
+    export_schema(&schema_for!(PriceRootResponse), &out_dir);
+    export_schema(&schema_for!(HealthScoreResponse), &out_dir);
+    export_schema(&schema_for!(ExecutionPriceResponse), &out_dir);
+    export_schema(&schema_for!(QuotedPriceResponse), &out_dir);
+    export_schema(&schema_for!(FullPriceResponse), &out_dir);
+    export_schema(&schema_for!(BigMoversResponse), &out_dir);
 }
@@ -2,22 +2,72 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    attr, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult,
+    attr, to_binary, Api, BankMsg, Binary, CanonicalAddr, Coin, ContractResult, CosmosMsg, Decimal,
+    Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    Uint128, WasmMsg,
 };
+use sha2::{Digest, Sha256};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
 
-use crate::math::decimal_division;
+use crate::error::ContractError;
+use crate::math::{
+    checked_amount_multiplication, checked_decimal_mean, checked_slippage_price, decimal_division,
+    decimal_multiplication, decimal_truncate,
+};
+use crate::migration::{migrate_asset_count, migrate_config, migrate_price_ema};
+use crate::querier::{query_source_price, query_token_decimals};
 use crate::state::{
-    read_config, read_feeder, read_price, read_prices, store_config, store_feeder, store_price,
-    Config, PriceInfo,
+    clear_alt_prices, is_feeder_suspended, read_all_prices, read_asset_config, read_asset_count,
+    read_asset_tokens, read_asset_tokens_with_prefix, read_config, read_feed_nonce, read_feeder,
+    read_feeder_feed_times, read_feeder_pubkey, read_feeders, read_idempotency_key,
+    read_indexes_with_component, read_pause_info, read_price, read_prices, read_scheduled_config,
+    read_snapshot, read_snapshot_labels, remove_asset_config, remove_feeder, remove_feeder_pubkey,
+    remove_price, remove_scheduled_config, remove_snapshot, reset_prices, store_asset_config,
+    store_asset_count, store_config, store_feed_nonce, store_feeder, store_feeder_feed_times,
+    store_feeder_pubkey, store_feeder_suspended, store_idempotency_key, store_pause_info,
+    store_price, store_scheduled_config, store_snapshot, store_snapshot_labels, AssetConfig,
+    Config, PauseInfo, PriceInfo, ScheduledConfig, Snapshot,
 };
 
 use mirror_protocol::common::OrderBy;
 use mirror_protocol::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, MigrateMsg, PriceResponse,
-    PricesResponse, PricesResponseElem, QueryMsg,
+    AllEffectivePricesResponse, AssetExistsResponse, AssetTokensResponse,
+    BatchPortfolioValueResponse, BigMoversResponse, ConfigChanges, ConfigResponse,
+    CurrentPriceSourceResponse, EffectivePricesResponseElem, ExecuteMsg, ExecutionPriceResponse,
+    FeederResponse, FeedersResponse, FeedersResponseElem, FrozenPolicy, FullPriceResponse,
+    HealthScoreResponse, InstantiateMsg, MarketIndexResponse, MigrateMsg, MostStaleResponse,
+    MostStaleResponseElem, OutOfBand, OverviewResponse, PathPriceResponse, PauseStatusResponse,
+    PriceAuthorization, PriceHookMsg, PriceResponse, PriceRootResponse, PricesMapResponse,
+    PricesResponse, PricesResponseElem, QueryMsg, QuotedPriceResponse, RawConfigResponse,
+    SafePriceResponse, SnapshotResponse, StaleAtResponse, StaleResponse, UpdateTimeDiffResponse,
 };
 
+/// Maximum number of asset tokens returned by the overview query
+const OVERVIEW_ASSET_LIMIT: usize = 100;
+/// Maximum number of assets returned by the most stale query
+const MOST_STALE_MAX_LIMIT: u32 = 10;
+/// Maximum length in bytes of the source reported with a price feed
+const MAX_SOURCE_LENGTH: usize = 64;
+/// Maximum length in bytes of the reason recorded when freezing an asset
+const MAX_PAUSE_REASON_LENGTH: usize = 256;
+/// Maximum length in bytes of an asset's custom staleness error
+const MAX_STALE_MESSAGE_LENGTH: usize = 256;
+/// Maximum number of contracts notified of an asset's price feeds
+const MAX_SUBSCRIBERS: usize = 10;
+/// Reply id of the price hooks sent to subscribers
+pub const PRICE_HOOK_REPLY_ID: u64 = 1;
+/// Gas each price hook may use. Failures are only caught within a gas limit,
+/// so without one a subscriber running out of gas would revert the feed
+pub const PRICE_HOOK_GAS_LIMIT: u64 = 300_000;
+/// Maximum number of price snapshots retained, the oldest is dropped first
+const MAX_SNAPSHOTS: usize = 10;
+/// Decimal has 18 fractional digits, so more decimals add no precision
+const MAX_QUOTED_DECIMALS: u8 = 18;
+/// Maximum number of holdings valued by a batch portfolio query, summed
+/// across all portfolios
+const MAX_PORTFOLIO_ENTRIES: usize = 100;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -30,6 +80,26 @@ pub fn instantiate(
         &Config {
             owner: deps.api.addr_canonicalize(&msg.owner)?,
             base_asset: msg.base_asset,
+            alt_base_asset: None,
+            price_validity_period: msg.price_validity_period,
+            allow_reset_all: msg.allow_reset_all.unwrap_or(false),
+            default_feeder: None,
+            max_assets: None,
+            display_decimals: None,
+            global_feeder_allowlist: vec![],
+            relay_contract: None,
+            feed_fee: None,
+            fee_collector: None,
+            on_stale: StaleResponse::Flag,
+            maintenance_window: None,
+            price_limits: None,
+            feeder_max_feeds_per_window: None,
+            frozen_query_policy: FrozenPolicy::ReturnLast,
+            min_multiplier: None,
+            max_multiplier: None,
+            multiplier_enabled: true,
+            post_unpause_grace: 0,
+            feeder_group: None,
         },
     )?;
 
@@ -37,181 +107,2932 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
-    match msg {
-        ExecuteMsg::UpdateConfig { owner } => try_update_config(deps, info, owner),
+pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let scheduled_config = apply_scheduled_config(deps.branch(), &env)?;
+
+    let res = match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            alt_base_asset,
+            price_validity_period,
+            default_feeder,
+            max_assets,
+            display_decimals,
+            global_feeder_allowlist,
+            relay_contract,
+            feed_fee,
+            fee_collector,
+            on_stale,
+            maintenance_window,
+            price_limits,
+            feeder_max_feeds_per_window,
+            frozen_query_policy,
+            min_multiplier,
+            max_multiplier,
+            multiplier_enabled,
+            post_unpause_grace,
+        } => try_update_config(
+            deps,
+            info,
+            ConfigChanges {
+                owner,
+                alt_base_asset,
+                price_validity_period,
+                default_feeder,
+                max_assets,
+                display_decimals,
+                global_feeder_allowlist,
+                relay_contract,
+                feed_fee,
+                fee_collector,
+                on_stale,
+                maintenance_window,
+                price_limits,
+                feeder_max_feeds_per_window,
+                frozen_query_policy,
+                min_multiplier,
+                max_multiplier,
+                multiplier_enabled,
+                post_unpause_grace,
+            },
+        ),
+        ExecuteMsg::ScheduleConfig {
+            changes,
+            effective_at,
+        } => try_schedule_config(deps, info, changes, effective_at),
+        ExecuteMsg::ApplyScheduledConfig {} => {
+            if scheduled_config == ScheduledConfigStatus::Pending {
+                return Err(ContractError::NoScheduledConfig {});
+            }
+            Ok(Response::new().add_attribute("action", "apply_scheduled_config"))
+        }
         ExecuteMsg::RegisterAsset {
             asset_token,
             feeder,
-        } => try_register_asset(deps, info, asset_token, feeder),
-        ExecuteMsg::FeedPrice { prices } => try_feed_price(deps, env, info, prices),
+            feeder_pubkey,
+            decimals,
+            fetch_decimals,
+        } => try_register_asset(
+            deps,
+            info,
+            asset_token,
+            feeder,
+            feeder_pubkey,
+            decimals,
+            fetch_decimals,
+        ),
+        ExecuteMsg::RegisterAndFeed {
+            asset_token,
+            feeder,
+            price,
+        } => try_register_and_feed(deps, env, info, asset_token, feeder, price),
+        ExecuteMsg::UpdateFeeders { updates } => try_update_feeders(deps, info, updates),
+        ExecuteMsg::RemoveAsset { asset_token, force } => {
+            try_remove_asset(deps, env, info, asset_token, force)
+        }
+        ExecuteMsg::UpdateToken {
+            asset_token,
+            new_asset_token,
+        } => try_update_token(deps, info, asset_token, new_asset_token),
+        ExecuteMsg::SuspendFeeder { feeder } => try_set_feeder_suspended(deps, info, feeder, true),
+        ExecuteMsg::ResumeFeeder { feeder } => try_set_feeder_suspended(deps, info, feeder, false),
+        ExecuteMsg::FreezeAsset {
+            asset_token,
+            pause_reason,
+        } => try_set_asset_frozen(deps, info, asset_token, true, pause_reason),
+        ExecuteMsg::UnfreezeAsset { asset_token } => {
+            try_set_asset_frozen(deps, info, asset_token, false, None)
+        }
+        ExecuteMsg::SetPaused { paused, reason } => try_set_paused(deps, env, info, paused, reason),
+        ExecuteMsg::MigrateToFeederGroup { group } => {
+            try_migrate_to_feeder_group(deps, info, group)
+        }
+        ExecuteMsg::UpdateAssetConfig {
+            asset_token,
+            ema_alpha,
+            slippage_per_unit,
+            subscribers,
+            price_band,
+            out_of_band,
+            basket,
+            price_multiplier,
+            multiplier_tiers,
+            max_deviation,
+            monotone_increasing,
+            peg,
+            peg_tolerance,
+            stale_message,
+            subscription,
+        } => try_update_asset_config(
+            deps,
+            env,
+            info,
+            asset_token,
+            ema_alpha,
+            slippage_per_unit,
+            subscribers,
+            price_band,
+            out_of_band,
+            basket,
+            price_multiplier,
+            multiplier_tiers,
+            max_deviation,
+            monotone_increasing,
+            peg,
+            peg_tolerance,
+            stale_message,
+            subscription,
+        ),
+        ExecuteMsg::Snapshot { label } => try_snapshot(deps, env, info, label),
+        ExecuteMsg::SetMultipliers { updates } => try_set_multipliers(deps, info, updates),
+        ExecuteMsg::SetReferencePrice { asset_token, price } => {
+            try_set_reference_price(deps, info, asset_token, price)
+        }
+        ExecuteMsg::FeedBasket {
+            index_token,
+            components,
+        } => try_feed_basket(deps, env, info, index_token, components),
+        ExecuteMsg::FeedPrice {
+            prices,
+            alt_prices,
+            source,
+            idempotency_key,
+            only_if_changed,
+        } => try_feed_price(
+            deps,
+            env,
+            info,
+            prices,
+            alt_prices,
+            source,
+            idempotency_key,
+            only_if_changed,
+        ),
+        ExecuteMsg::FeedPriceSigned {
+            asset_token,
+            price,
+            nonce,
+            signature,
+        } => try_feed_price_signed(deps, env, info, asset_token, price, nonce, signature),
+        ExecuteMsg::Heartbeat { asset_token } => try_heartbeat(deps, env, info, asset_token),
+        ExecuteMsg::Subscribe { asset_token } => try_subscribe(deps, env, info, asset_token),
+        ExecuteMsg::ResetAllPrices {} => try_reset_all_prices(deps, info),
+        ExecuteMsg::SetLastUpdateTime { asset_token, time } => {
+            try_set_last_update_time(deps, env, info, asset_token, time)
+        }
+        ExecuteMsg::ImportPrices {
+            source_oracle,
+            asset_tokens,
+        } => try_import_prices(deps, info, source_oracle, asset_tokens),
+    }?;
+
+    // changes that no longer validate are dropped rather than failing every message
+    if scheduled_config == ScheduledConfigStatus::Dropped {
+        return Ok(res.add_attribute("scheduled_config", "dropped"));
     }
+    Ok(res)
 }
 
 pub fn try_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    owner: Option<String>,
-) -> StdResult<Response> {
+    changes: ConfigChanges,
+) -> Result<Response, ContractError> {
     let mut config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
-    if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+    if changes == ConfigChanges::default() {
+        return Err(ContractError::NothingToUpdate {});
     }
 
+    let alt_base_asset = config.alt_base_asset.clone();
+    apply_config_changes(deps.api, &mut config, changes)?;
+
+    // the scheduled changes must still apply on top of the updated config
+    if let Some(scheduled_config) = read_scheduled_config(deps.storage)? {
+        apply_config_changes(deps.api, &mut config.clone(), scheduled_config.changes)?;
+    }
+
+    if config.alt_base_asset != alt_base_asset {
+        clear_alt_prices(deps.storage)?;
+    }
     store_config(deps.storage, &config)?;
     Ok(Response::default())
 }
 
+fn apply_config_changes(
+    api: &dyn Api,
+    config: &mut Config,
+    changes: ConfigChanges,
+) -> Result<(), ContractError> {
+    if let Some(owner) = changes.owner {
+        config.owner = api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(alt_base_asset) = changes.alt_base_asset {
+        if alt_base_asset == config.base_asset {
+            return Err(ContractError::InvalidAltBaseAsset {});
+        }
+        config.alt_base_asset = Some(alt_base_asset);
+    }
+
+    if let Some(price_validity_period) = changes.price_validity_period {
+        config.price_validity_period = price_validity_period;
+    }
+
+    if let Some(default_feeder) = changes.default_feeder {
+        config.default_feeder = if default_feeder.is_empty() {
+            None
+        } else {
+            Some(api.addr_canonicalize(&default_feeder)?)
+        };
+    }
+
+    if let Some(max_assets) = changes.max_assets {
+        config.max_assets = Some(max_assets).filter(|max_assets| *max_assets > 0);
+    }
+
+    if let Some(display_decimals) = changes.display_decimals {
+        if display_decimals > 18 {
+            return Err(ContractError::InvalidDisplayDecimals {});
+        }
+        // Decimal has 18 fractional digits, so truncating to 18 is a no-op
+        config.display_decimals = Some(display_decimals).filter(|decimals| *decimals < 18);
+    }
+
+    if let Some(global_feeder_allowlist) = changes.global_feeder_allowlist {
+        config.global_feeder_allowlist = global_feeder_allowlist
+            .iter()
+            .map(|feeder| api.addr_canonicalize(feeder))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    }
+
+    if let Some(relay_contract) = changes.relay_contract {
+        config.relay_contract = if relay_contract.is_empty() {
+            None
+        } else {
+            Some(api.addr_canonicalize(&relay_contract)?)
+        };
+    }
+
+    if let Some(feed_fee) = changes.feed_fee {
+        config.feed_fee = Some(feed_fee).filter(|feed_fee| !feed_fee.amount.is_zero());
+    }
+
+    if let Some(fee_collector) = changes.fee_collector {
+        config.fee_collector = if fee_collector.is_empty() {
+            None
+        } else {
+            Some(api.addr_canonicalize(&fee_collector)?)
+        };
+    }
+
+    if let Some(on_stale) = changes.on_stale {
+        config.on_stale = on_stale;
+    }
+
+    if let Some((start, end)) = changes.maintenance_window {
+        config.maintenance_window = if start < end {
+            Some((start, end))
+        } else {
+            None
+        };
+    }
+
+    if let Some((count, window)) = changes.feeder_max_feeds_per_window {
+        config.feeder_max_feeds_per_window = if count > 0 && window > 0 {
+            Some((count, window))
+        } else {
+            None
+        };
+    }
+
+    if let Some(frozen_query_policy) = changes.frozen_query_policy {
+        config.frozen_query_policy = frozen_query_policy;
+    }
+
+    if let Some((min_price, max_price)) = changes.price_limits {
+        if min_price > max_price {
+            return Err(ContractError::InvalidPriceLimits {});
+        }
+        config.price_limits = Some((min_price, max_price)).filter(|(_, max)| !max.is_zero());
+    }
+
+    if let Some(min_multiplier) = changes.min_multiplier {
+        config.min_multiplier = Some(min_multiplier).filter(|min| !min.is_zero());
+    }
+
+    if let Some(max_multiplier) = changes.max_multiplier {
+        config.max_multiplier = Some(max_multiplier).filter(|max| !max.is_zero());
+    }
+
+    if let Some(multiplier_enabled) = changes.multiplier_enabled {
+        config.multiplier_enabled = multiplier_enabled;
+    }
+
+    if let Some(post_unpause_grace) = changes.post_unpause_grace {
+        config.post_unpause_grace = post_unpause_grace;
+    }
+
+    if let (Some(min_multiplier), Some(max_multiplier)) =
+        (config.min_multiplier, config.max_multiplier)
+    {
+        if min_multiplier > max_multiplier {
+            return Err(ContractError::InvalidMultiplierBounds {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Config changes are validated against the current config when scheduled,
+/// and UpdateConfig rejects changes the scheduled ones would not apply on top of
+pub fn try_schedule_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    changes: ConfigChanges,
+    effective_at: u64,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if changes == ConfigChanges::default() {
+        return Err(ContractError::NothingToUpdate {});
+    }
+
+    apply_config_changes(deps.api, &mut config, changes.clone())?;
+    store_scheduled_config(
+        deps.storage,
+        &ScheduledConfig {
+            changes,
+            effective_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "schedule_config"),
+        attr("effective_at", effective_at.to_string()),
+    ]))
+}
+
+/// What happened to the scheduled config at the start of a message
+#[derive(PartialEq)]
+enum ScheduledConfigStatus {
+    /// nothing is scheduled or it is not effective yet
+    Pending,
+    Applied,
+    /// the changes no longer validated against the config and were discarded
+    Dropped,
+}
+
+/// Applies the scheduled config once its effective time is reached. Changes
+/// that fail to apply are dropped so they can not block every other message
+fn apply_scheduled_config(
+    deps: DepsMut,
+    env: &Env,
+) -> Result<ScheduledConfigStatus, ContractError> {
+    let scheduled_config = match read_scheduled_config(deps.storage)? {
+        Some(scheduled_config) if scheduled_config.effective_at <= env.block.time.seconds() => {
+            scheduled_config
+        }
+        _ => return Ok(ScheduledConfigStatus::Pending),
+    };
+    remove_scheduled_config(deps.storage);
+
+    let mut config: Config = read_config(deps.storage)?;
+    let alt_base_asset = config.alt_base_asset.clone();
+    if apply_config_changes(deps.api, &mut config, scheduled_config.changes).is_err() {
+        return Ok(ScheduledConfigStatus::Dropped);
+    }
+    if config.alt_base_asset != alt_base_asset {
+        clear_alt_prices(deps.storage)?;
+    }
+    store_config(deps.storage, &config)?;
+
+    Ok(ScheduledConfigStatus::Applied)
+}
+
 pub fn try_register_asset(
     deps: DepsMut,
     info: MessageInfo,
     asset_token: String,
-    feeder: String,
-) -> StdResult<Response> {
+    feeder: Option<String>,
+    feeder_pubkey: Option<Binary>,
+    decimals: Option<u8>,
+    fetch_decimals: bool,
+) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(StdError::generic_err("unauthorized"));
+        return Err(ContractError::Unauthorized {});
     }
 
-    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let asset_token_raw = deps
+        .api
+        .addr_canonicalize(&asset_token)
+        .map_err(|_| ContractError::InvalidTokenAddress {})?;
+    assert_not_reserved_token(deps.as_ref(), &config, &asset_token_raw)?;
+
+    let feeder_raw = match feeder {
+        Some(feeder) => deps
+            .api
+            .addr_canonicalize(&feeder)
+            .map_err(|_| ContractError::InvalidFeederAddress {})?,
+        None => config.default_feeder.ok_or(ContractError::NoFeeder {})?,
+    };
 
     // check if it is a new asset
     if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        let asset_count = read_asset_count(deps.storage)?;
+        if let Some(max_assets) = config.max_assets {
+            if asset_count >= max_assets {
+                return Err(ContractError::TooManyAssets { max_assets });
+            }
+        }
+        store_asset_count(deps.storage, asset_count + 1)?;
+
         store_price(
             deps.storage,
             &asset_token_raw,
             &PriceInfo {
                 price: Decimal::zero(),
                 last_updated_time: 0u64,
+                alt_price: None,
+                alt_last_updated_time: 0u64,
+                ema: Decimal::zero(),
+                source: None,
+                prev_price: Decimal::zero(),
+                total_update_interval: 0u64,
+                update_interval_count: 0u64,
+                was_clamped: false,
+                update_count: 0u64,
+                set_by: None,
             },
         )?;
     }
 
     // update/store feeder
-    store_feeder(
+    store_feeder(deps.storage, &asset_token_raw, &feeder_raw)?;
+
+    // a pubkey belongs to the feeder, so it is replaced along with it
+    if let Some(feeder_pubkey) = feeder_pubkey {
+        if feeder_pubkey.len() != 33 && feeder_pubkey.len() != 65 {
+            return Err(ContractError::InvalidFeederPubkey {});
+        }
+        store_feeder_pubkey(deps.storage, &asset_token_raw, &feeder_pubkey)?;
+    } else {
+        remove_feeder_pubkey(deps.storage, &asset_token_raw);
+    }
+
+    let decimals = if fetch_decimals {
+        Some(query_token_decimals(&deps.querier, asset_token)?)
+    } else {
+        decimals
+    };
+    if let Some(decimals) = decimals {
+        let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+        asset_config.decimals = Some(decimals);
+        store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+    }
+
+    Ok(Response::default())
+}
+
+pub fn try_register_and_feed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    feeder: Option<String>,
+    price: Decimal,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let owner_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let asset_token_raw = deps
+        .api
+        .addr_canonicalize(&asset_token)
+        .map_err(|_| ContractError::InvalidTokenAddress {})?;
+    // registering over an existing asset would replace its feeder and drop its pubkey
+    if read_feeder(deps.storage, &asset_token_raw).is_ok() {
+        return Err(ContractError::AssetAlreadyRegistered {});
+    }
+    if price.is_zero() {
+        return Err(ContractError::ZeroPrice {});
+    }
+    assert_not_frozen(deps.storage, &asset_token_raw)?;
+    assert_price_within_limits(&read_config(deps.storage)?, price)?;
+
+    try_register_asset(
+        deps.branch(),
+        info,
+        asset_token.clone(),
+        feeder,
+        None,
+        None,
+        false,
+    )?;
+
+    update_price(
         deps.storage,
         &asset_token_raw,
-        &deps.api.addr_canonicalize(&feeder)?,
+        price,
+        env.block.time.seconds(),
+        None,
+        (owner_raw, PriceAuthorization::Owner),
     )?;
 
-    Ok(Response::default())
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_and_feed"),
+        attr("asset_token", asset_token),
+        attr("price", price.to_string()),
+    ]))
 }
 
-pub fn try_feed_price(
+pub fn try_update_feeders(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    prices: Vec<(String, Decimal)>,
-) -> StdResult<Response> {
-    let feeder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-
-    let mut attributes = vec![attr("action", "price_feed")];
-    for price in prices {
-        attributes.push(attr("asset", price.0.to_string()));
-        attributes.push(attr("price", price.1.to_string()));
+    updates: Vec<(String, String)>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
 
-        // Check feeder permission
-        let asset_token_raw = deps.api.addr_canonicalize(&price.0)?;
-        if feeder_raw != read_feeder(deps.storage, &asset_token_raw)? {
-            return Err(StdError::generic_err("unauthorized"));
+    // validate the whole batch before writing anything
+    let mut feeders: Vec<(CanonicalAddr, CanonicalAddr)> = vec![];
+    for (asset_token, feeder) in updates.iter() {
+        let asset_token_raw = deps.api.addr_canonicalize(asset_token)?;
+        if read_feeder(deps.storage, &asset_token_raw).is_err() {
+            return Err(ContractError::AssetNotFound {});
         }
+        feeders.push((asset_token_raw, deps.api.addr_canonicalize(feeder)?));
+    }
 
-        let mut state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
-        state.last_updated_time = env.block.time.seconds();
-        state.price = price.1;
+    let mut attributes = vec![attr("action", "update_feeders")];
+    for ((asset_token, feeder), (asset_token_raw, feeder_raw)) in updates.iter().zip(feeders) {
+        store_feeder(deps.storage, &asset_token_raw, &feeder_raw)?;
+        // a pubkey belongs to the feeder, so it is dropped along with it
+        remove_feeder_pubkey(deps.storage, &asset_token_raw);
 
-        store_price(deps.storage, &asset_token_raw, &state)?;
+        attributes.push(attr("asset_token", asset_token));
+        attributes.push(attr("feeder", feeder));
     }
 
     Ok(Response::new().add_attributes(attributes))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::Feeder { asset_token } => to_binary(&query_feeder(deps, asset_token)?),
-        QueryMsg::Price {
-            base_asset,
-            quote_asset,
-        } => to_binary(&query_price(deps, base_asset, quote_asset)?),
-        QueryMsg::Prices {
-            start_after,
-            limit,
-            order_by,
-        } => to_binary(&query_prices(deps, start_after, limit, order_by)?),
+pub fn try_remove_asset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    force: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
     }
-}
 
-fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let state = read_config(deps.storage)?;
-    let resp = ConfigResponse {
-        owner: deps.api.addr_humanize(&state.owner)?.to_string(),
-        base_asset: state.base_asset,
-    };
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
 
-    Ok(resp)
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    if !force && is_fresh(&config, &price_info, env.block.time.seconds()) {
+        return Err(ContractError::FreshPrice {});
+    }
+
+    // an index can not be priced without every component, even when forced
+    if !read_indexes_with_component(deps.storage, &asset_token_raw)?.is_empty() {
+        return Err(ContractError::AssetInBasket {});
+    }
+
+    let asset_count = read_asset_count(deps.storage)?;
+    store_asset_count(deps.storage, asset_count.saturating_sub(1))?;
+
+    // the feed nonce is kept so signatures can not be replayed if the asset is registered again
+    remove_feeder(deps.storage, &asset_token_raw);
+    remove_feeder_pubkey(deps.storage, &asset_token_raw);
+    remove_price(deps.storage, &asset_token_raw);
+    remove_asset_config(deps.storage, &asset_token_raw);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_asset"),
+        attr("asset_token", asset_token),
+    ]))
 }
 
-fn query_feeder(deps: Deps, asset_token: String) -> StdResult<FeederResponse> {
-    let feeder = read_feeder(deps.storage, &deps.api.addr_canonicalize(&asset_token)?)?;
-    let resp = FeederResponse {
-        asset_token,
-        feeder: deps.api.addr_humanize(&feeder)?.to_string(),
-    };
+/// Prices are denominated in the base assets, so none of them can be an asset
+/// token, including in a different case
+fn assert_not_reserved_token(
+    deps: Deps,
+    config: &Config,
+    asset_token_raw: &CanonicalAddr,
+) -> Result<(), ContractError> {
+    for base_asset in [Some(&config.base_asset), config.alt_base_asset.as_ref()]
+        .iter()
+        .flatten()
+    {
+        // a native denom is usually not a valid address, and so never collides
+        if deps.api.addr_canonicalize(base_asset).ok().as_ref() == Some(asset_token_raw) {
+            return Err(ContractError::ReservedToken {});
+        }
+    }
 
-    Ok(resp)
+    Ok(())
 }
 
-fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceResponse> {
+pub fn try_update_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: String,
+    new_asset_token: String,
+) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
-    let quote_price = if config.base_asset == quote {
-        PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: u64::MAX,
-        }
-    } else {
-        read_price(deps.storage, &deps.api.addr_canonicalize(quote.as_str())?)?
-    };
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    let base_price = if config.base_asset == base {
-        PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: u64::MAX,
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let feeder_raw =
+        read_feeder(deps.storage, &asset_token_raw).map_err(|_| ContractError::AssetNotFound {})?;
+
+    let new_asset_token_raw = deps.api.addr_canonicalize(&new_asset_token)?;
+    assert_not_reserved_token(deps.as_ref(), &config, &new_asset_token_raw)?;
+    if read_feeder(deps.storage, &new_asset_token_raw).is_ok() {
+        return Err(ContractError::AssetAlreadyRegistered {});
+    }
+
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    store_feeder(deps.storage, &new_asset_token_raw, &feeder_raw)?;
+    store_price(deps.storage, &new_asset_token_raw, &price_info)?;
+    store_asset_config(deps.storage, &new_asset_token_raw, &asset_config)?;
+    if let Ok(feeder_pubkey) = read_feeder_pubkey(deps.storage, &asset_token_raw) {
+        store_feeder_pubkey(deps.storage, &new_asset_token_raw, &feeder_pubkey)?;
+    }
+
+    // indexes keep their component under the new token
+    for (index_token_raw, mut index_config) in
+        read_indexes_with_component(deps.storage, &asset_token_raw)?
+    {
+        for (component_raw, _) in index_config.basket.iter_mut() {
+            if *component_raw == asset_token_raw {
+                *component_raw = new_asset_token_raw.clone();
+            }
         }
-    } else {
-        read_price(deps.storage, &deps.api.addr_canonicalize(base.as_str())?)?
-    };
+        store_asset_config(deps.storage, &index_token_raw, &index_config)?;
+    }
 
-    Ok(PriceResponse {
-        rate: decimal_division(base_price.price, quote_price.price),
-        last_updated_base: base_price.last_updated_time,
-        last_updated_quote: quote_price.last_updated_time,
-    })
+    // signed feeds commit to the asset token, so the old nonce is kept as on removal
+    remove_feeder(deps.storage, &asset_token_raw);
+    remove_feeder_pubkey(deps.storage, &asset_token_raw);
+    remove_price(deps.storage, &asset_token_raw);
+    remove_asset_config(deps.storage, &asset_token_raw);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_token"),
+        attr("asset_token", asset_token),
+        attr("new_asset_token", new_asset_token),
+    ]))
 }
 
-fn query_prices(
-    deps: Deps,
-    start_after: Option<String>,
-    limit: Option<u32>,
-    order_by: Option<OrderBy>,
-) -> StdResult<PricesResponse> {
-    let start_after = if let Some(start_after) = start_after {
-        Some(deps.api.addr_canonicalize(&start_after)?)
-    } else {
-        None
-    };
+/// Multiplier applied to a fed price, picked by the tier the price falls into.
+/// Stored multipliers are ignored while multipliers are disabled
+fn price_multiplier(config: &Config, asset_config: &AssetConfig, price: Decimal) -> Decimal {
+    if !config.multiplier_enabled {
+        return Decimal::one();
+    }
 
-    let prices: Vec<PricesResponseElem> = read_prices(deps, start_after, limit, order_by)?;
+    asset_config
+        .multiplier_tiers
+        .iter()
+        .rev()
+        .find(|(threshold, _)| price >= *threshold)
+        .map(|(_, multiplier)| *multiplier)
+        .or(asset_config.price_multiplier)
+        .unwrap_or_else(Decimal::one)
+}
 
-    Ok(PricesResponse { prices })
+/// A price is fresh when it was updated within the price validity period
+fn is_fresh(config: &Config, price_info: &PriceInfo, now: u64) -> bool {
+    price_info.last_updated_time != 0
+        && price_info
+            .last_updated_time
+            .saturating_add(config.price_validity_period)
+            >= now
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+/// Returns the asset's custom staleness error, falling back to `generic`
+fn stale_error(asset_config: &AssetConfig, generic: String) -> StdError {
+    StdError::generic_err(asset_config.stale_message.clone().unwrap_or(generic))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_update_asset_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    ema_alpha: Option<Decimal>,
+    slippage_per_unit: Option<Decimal>,
+    subscribers: Option<Vec<String>>,
+    price_band: Option<(Decimal, Decimal)>,
+    out_of_band: Option<OutOfBand>,
+    basket: Option<Vec<(String, Decimal)>>,
+    price_multiplier: Option<Decimal>,
+    multiplier_tiers: Option<Vec<(Decimal, Decimal)>>,
+    max_deviation: Option<Decimal>,
+    monotone_increasing: Option<bool>,
+    peg: Option<Decimal>,
+    peg_tolerance: Option<Decimal>,
+    stale_message: Option<String>,
+    subscription: Option<(Coin, u64)>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    if let Some(ema_alpha) = ema_alpha {
+        if ema_alpha.is_zero() || ema_alpha > Decimal::one() {
+            return Err(ContractError::InvalidEmaAlpha {});
+        }
+        asset_config.ema_alpha = Some(ema_alpha);
+    }
+
+    if let Some(slippage_per_unit) = slippage_per_unit {
+        asset_config.slippage_per_unit = Some(slippage_per_unit);
+    }
+
+    if let Some(subscribers) = subscribers {
+        // paid subscribers count against the same limit
+        let now = env.block.time.seconds();
+        asset_config
+            .paid_subscribers
+            .retain(|(_, expires_at)| *expires_at > now);
+        if subscribers.len() + asset_config.paid_subscribers.len() > MAX_SUBSCRIBERS {
+            return Err(ContractError::TooManySubscribers {
+                max_subscribers: MAX_SUBSCRIBERS,
+            });
+        }
+        asset_config.subscribers = subscribers
+            .iter()
+            .map(|subscriber| deps.api.addr_canonicalize(subscriber))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    }
+
+    if let Some((min_price, max_price)) = price_band {
+        if min_price > max_price {
+            return Err(ContractError::InvalidPriceBand {});
+        }
+        asset_config.price_band = Some((min_price, max_price));
+    }
+
+    if let Some(out_of_band) = out_of_band {
+        asset_config.out_of_band = out_of_band;
+    }
+
+    if let Some(basket) = basket {
+        let mut basket_raw: Vec<(CanonicalAddr, Decimal)> = vec![];
+        for (component, units) in basket.iter() {
+            let component_raw = deps.api.addr_canonicalize(component)?;
+            if units.is_zero()
+                || component_raw == asset_token_raw
+                || read_feeder(deps.storage, &component_raw).is_err()
+                || basket_raw.iter().any(|(raw, _)| *raw == component_raw)
+            {
+                return Err(ContractError::InvalidBasket {});
+            }
+            basket_raw.push((component_raw, *units));
+        }
+        asset_config.basket = basket_raw;
+    }
+
+    if let Some(price_multiplier) = price_multiplier {
+        assert_multiplier_allowed(&config, price_multiplier)?;
+        asset_config.price_multiplier = Some(price_multiplier);
+    }
+
+    if let Some(mut multiplier_tiers) = multiplier_tiers {
+        multiplier_tiers.sort_by_key(|tier| tier.0);
+        if multiplier_tiers
+            .windows(2)
+            .any(|tiers| tiers[0].0 == tiers[1].0)
+        {
+            return Err(ContractError::InvalidMultiplier {});
+        }
+        for (_, multiplier) in multiplier_tiers.iter() {
+            assert_multiplier_allowed(&config, *multiplier)?;
+        }
+        asset_config.multiplier_tiers = multiplier_tiers;
+    }
+
+    if let Some(max_deviation) = max_deviation {
+        asset_config.max_deviation = Some(max_deviation);
+    }
+
+    if let Some(monotone_increasing) = monotone_increasing {
+        asset_config.monotone_increasing = monotone_increasing;
+    }
+
+    if let Some(peg) = peg {
+        if peg.is_zero() {
+            return Err(ContractError::InvalidPeg {});
+        }
+        asset_config.peg = Some(peg);
+    }
+
+    if let Some(peg_tolerance) = peg_tolerance {
+        asset_config.peg_tolerance = Some(peg_tolerance);
+    }
+
+    if let Some(stale_message) = stale_message {
+        if stale_message.len() > MAX_STALE_MESSAGE_LENGTH {
+            return Err(ContractError::StaleMessageTooLong {
+                max_length: MAX_STALE_MESSAGE_LENGTH,
+            });
+        }
+        asset_config.stale_message = if stale_message.is_empty() {
+            None
+        } else {
+            Some(stale_message)
+        };
+    }
+
+    if let Some((fee, duration)) = subscription {
+        asset_config.subscription = if duration == 0 {
+            None
+        } else {
+            Some((fee, duration))
+        };
+    }
+
+    asset_config.action_count += 1;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_asset_config"),
+        attr("asset_token", asset_token),
+    ]))
+}
+
+pub fn try_snapshot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    label: String,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let snapshot = Snapshot {
+        taken_at: env.block.time.seconds(),
+        prices: read_all_prices(deps.storage)?
+            .into_iter()
+            .map(|(asset_token_raw, price_info)| {
+                Ok(PricesResponseElem {
+                    asset_token: deps.api.addr_humanize(&asset_token_raw)?.to_string(),
+                    price: price_info.price,
+                    last_updated_time: price_info.last_updated_time,
+                })
+            })
+            .collect::<StdResult<Vec<PricesResponseElem>>>()?,
+    };
+    store_snapshot(deps.storage, &label, &snapshot)?;
+
+    let mut labels: Vec<String> = read_snapshot_labels(deps.storage)?;
+    labels.retain(|snapshot_label| *snapshot_label != label);
+    labels.push(label.clone());
+    if labels.len() > MAX_SNAPSHOTS {
+        remove_snapshot(deps.storage, &labels.remove(0));
+    }
+    store_snapshot_labels(deps.storage, &labels)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "snapshot"),
+        attr("label", label),
+        attr("asset_count", snapshot.prices.len().to_string()),
+    ]))
+}
+
+pub fn try_set_multipliers(
+    deps: DepsMut,
+    info: MessageInfo,
+    updates: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "set_multipliers")];
+    for (asset_token, price_multiplier) in updates {
+        let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+        if read_feeder(deps.storage, &asset_token_raw).is_err() {
+            return Err(ContractError::AssetNotFound {});
+        }
+        assert_multiplier_allowed(&config, price_multiplier)?;
+
+        let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+        asset_config.price_multiplier = Some(price_multiplier);
+        asset_config.action_count += 1;
+        store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+        attributes.push(attr("asset_token", asset_token));
+        attributes.push(attr("price_multiplier", price_multiplier.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn try_set_reference_price(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: String,
+    price: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+
+    // feeds are deviation-checked relative to the reference price
+    if price.is_some_and(|price| price.is_zero()) {
+        return Err(ContractError::InvalidReferencePrice {});
+    }
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.reference_price = price;
+    asset_config.action_count += 1;
+
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_reference_price"),
+        attr("asset_token", asset_token),
+        attr(
+            "reference_price",
+            price.map_or_else(|| "none".to_string(), |price| price.to_string()),
+        ),
+    ]))
+}
+
+pub fn try_set_feeder_suspended(
+    deps: DepsMut,
+    info: MessageInfo,
+    feeder: String,
+    suspended: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let feeder_raw = deps.api.addr_canonicalize(&feeder)?;
+    store_feeder_suspended(deps.storage, &feeder_raw, suspended)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr(
+            "action",
+            if suspended {
+                "suspend_feeder"
+            } else {
+                "resume_feeder"
+            },
+        ),
+        attr("feeder", feeder),
+    ]))
+}
+
+pub fn try_set_asset_frozen(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: String,
+    frozen: bool,
+    pause_reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+
+    if let Some(pause_reason) = &pause_reason {
+        if pause_reason.len() > MAX_PAUSE_REASON_LENGTH {
+            return Err(ContractError::PauseReasonTooLong {
+                max_length: MAX_PAUSE_REASON_LENGTH,
+            });
+        }
+    }
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.frozen = frozen;
+    asset_config.pause_reason = pause_reason;
+    asset_config.action_count += 1;
+
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+    Ok(Response::new().add_attributes(vec![
+        attr(
+            "action",
+            if frozen {
+                "freeze_asset"
+            } else {
+                "unfreeze_asset"
+            },
+        ),
+        attr("asset_token", asset_token),
+    ]))
+}
+
+pub fn try_set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(reason) = &reason {
+        if reason.len() > MAX_PAUSE_REASON_LENGTH {
+            return Err(ContractError::PauseReasonTooLong {
+                max_length: MAX_PAUSE_REASON_LENGTH,
+            });
+        }
+    }
+
+    store_pause_info(
+        deps.storage,
+        &PauseInfo {
+            paused,
+            reason,
+            since: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_paused"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
+pub fn try_migrate_to_feeder_group(
+    deps: DepsMut,
+    info: MessageInfo,
+    group: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.feeder_group = group
+        .as_ref()
+        .map(|group| deps.api.addr_canonicalize(group))
+        .transpose()?;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_to_feeder_group"),
+        attr("group", group.unwrap_or_default()),
+    ]))
+}
+
+fn assert_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if read_pause_info(storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    Ok(())
+}
+
+/// Feeds are rejected while the block time is within the maintenance window
+fn assert_not_in_maintenance(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    if let Some((start, end)) = read_config(storage)?.maintenance_window {
+        let now = env.block.time.seconds();
+        if start <= now && now < end {
+            return Err(ContractError::MaintenanceWindow {});
+        }
+    }
+
+    Ok(())
+}
+
+/// A non-empty global allowlist restricts feeding to the listed feeders,
+/// even for assets they are registered on
+fn assert_feeder_allowlisted(
+    storage: &dyn Storage,
+    feeder_raw: &CanonicalAddr,
+) -> Result<(), ContractError> {
+    let config: Config = read_config(storage)?;
+    if !config.global_feeder_allowlist.is_empty()
+        && !config.global_feeder_allowlist.contains(feeder_raw)
+    {
+        return Err(ContractError::FeederNotAllowlisted {});
+    }
+
+    Ok(())
+}
+
+/// Besides the asset's own feeder, or the feeder group in its place, the
+/// relay contract may feed any registered asset. Returns how the feeder is
+/// authorized
+fn assert_feeder_authorized(
+    storage: &dyn Storage,
+    config: &Config,
+    feeder_raw: &CanonicalAddr,
+    asset_token_raw: &CanonicalAddr,
+) -> Result<PriceAuthorization, ContractError> {
+    let asset_feeder = read_feeder(storage, asset_token_raw)?;
+    match &config.feeder_group {
+        Some(feeder_group) if feeder_group == feeder_raw => Ok(PriceAuthorization::Group),
+        None if asset_feeder == *feeder_raw => Ok(PriceAuthorization::Feeder),
+        _ if config.relay_contract.as_ref() == Some(feeder_raw) => Ok(PriceAuthorization::Relay),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Records the feed against the feeder's rate limit, keeping only the
+/// feed times still inside the rolling window
+fn assert_feed_rate_limit(
+    storage: &mut dyn Storage,
+    config: &Config,
+    feeder_raw: &CanonicalAddr,
+    now: u64,
+) -> Result<(), ContractError> {
+    if let Some((count, window)) = config.feeder_max_feeds_per_window {
+        let mut feed_times: Vec<u64> = read_feeder_feed_times(storage, feeder_raw)?
+            .into_iter()
+            .filter(|feed_time| feed_time.saturating_add(window) > now)
+            .collect();
+        if feed_times.len() >= count as usize {
+            return Err(ContractError::FeedRateLimited {});
+        }
+
+        feed_times.push(now);
+        store_feeder_feed_times(storage, feeder_raw, &feed_times)?;
+    }
+
+    Ok(())
+}
+
+/// Feeds outside the absolute price limits are rejected rather than clamped,
+/// unlike the per-asset price band they point at a broken feeder
+fn assert_price_within_limits(config: &Config, price: Decimal) -> Result<(), ContractError> {
+    if let Some((min_price, max_price)) = config.price_limits {
+        if price < min_price {
+            return Err(ContractError::PriceBelowMin { min_price });
+        }
+        if price > max_price {
+            return Err(ContractError::PriceAboveMax { max_price });
+        }
+    }
+
+    Ok(())
+}
+
+/// A multiplier must be non-zero and within the configured bounds, checked
+/// when it is set so existing multipliers are left alone by new bounds
+fn assert_multiplier_allowed(config: &Config, multiplier: Decimal) -> Result<(), ContractError> {
+    if multiplier.is_zero() {
+        return Err(ContractError::InvalidMultiplier {});
+    }
+    if !config.multiplier_enabled && multiplier != Decimal::one() {
+        return Err(ContractError::MultiplierDisabled {});
+    }
+    if config.min_multiplier.is_some_and(|min| multiplier < min)
+        || config.max_multiplier.is_some_and(|max| multiplier > max)
+    {
+        return Err(ContractError::MultiplierOutOfBounds {});
+    }
+
+    Ok(())
+}
+
+/// Frozen assets do not accept any price feed until unfrozen
+fn assert_not_frozen(
+    storage: &dyn Storage,
+    asset_token_raw: &CanonicalAddr,
+) -> Result<(), ContractError> {
+    if read_asset_config(storage, asset_token_raw)?.frozen {
+        return Err(ContractError::AssetFrozen {});
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_feed_price(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prices: Vec<(String, Decimal)>,
+    alt_prices: Option<Vec<(String, Decimal)>>,
+    source: Option<String>,
+    idempotency_key: Option<String>,
+    only_if_changed: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    assert_not_in_maintenance(deps.storage, &env)?;
+    let feeder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if is_feeder_suspended(deps.storage, &feeder_raw)? {
+        return Err(ContractError::FeederSuspended {});
+    }
+    assert_feeder_allowlisted(deps.storage, &feeder_raw)?;
+    let config: Config = read_config(deps.storage)?;
+    assert_feed_rate_limit(deps.storage, &config, &feeder_raw, env.block.time.seconds())?;
+    let messages: Vec<CosmosMsg> = feed_fee_messages(deps.as_ref(), &config, &info)?;
+    let mut hook_messages: Vec<SubMsg> = vec![];
+
+    // a repeated asset would silently keep only its last price
+    for prices in [Some(&prices), alt_prices.as_ref()].iter().flatten() {
+        let mut asset_tokens: Vec<CanonicalAddr> = vec![];
+        for (asset_token, _) in prices.iter() {
+            let asset_token_raw = deps.api.addr_canonicalize(asset_token)?;
+            if asset_tokens.contains(&asset_token_raw) {
+                return Err(ContractError::DuplicateAsset {
+                    asset_token: asset_token.to_string(),
+                });
+            }
+            asset_tokens.push(asset_token_raw);
+        }
+    }
+
+    if let Some(idempotency_key) = &idempotency_key {
+        let asset_tokens = prices
+            .iter()
+            .chain(alt_prices.iter().flatten())
+            .map(|price| deps.api.addr_canonicalize(&price.0))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+
+        // a retried message reuses the key for every asset it feeds, it is
+        // only acknowledged for a sender still allowed to feed them
+        let mut is_duplicate = !asset_tokens.is_empty();
+        for asset_token_raw in asset_tokens.iter() {
+            assert_feeder_authorized(deps.storage, &config, &feeder_raw, asset_token_raw)?;
+            let last_key = read_idempotency_key(deps.storage, asset_token_raw, &feeder_raw)?;
+            is_duplicate &= last_key.as_ref() == Some(idempotency_key);
+        }
+
+        if !is_duplicate {
+            for asset_token_raw in asset_tokens.iter() {
+                store_idempotency_key(deps.storage, asset_token_raw, &feeder_raw, idempotency_key)?;
+            }
+        } else {
+            return Ok(Response::new().add_messages(messages).add_attributes(vec![
+                attr("action", "price_feed"),
+                attr("idempotency_key", idempotency_key),
+                attr("duplicate", "true"),
+            ]));
+        }
+    }
+
+    let mut attributes = vec![attr("action", "price_feed")];
+    if let Some(source) = &source {
+        if source.len() > MAX_SOURCE_LENGTH {
+            return Err(ContractError::SourceTooLong {
+                max_length: MAX_SOURCE_LENGTH,
+            });
+        }
+        attributes.push(attr("source", source));
+    }
+
+    for price in prices {
+        // Check feeder permission
+        let asset_token_raw = deps.api.addr_canonicalize(&price.0)?;
+        let authorization =
+            assert_feeder_authorized(deps.storage, &config, &feeder_raw, &asset_token_raw)?;
+        assert_not_frozen(deps.storage, &asset_token_raw)?;
+        assert_price_within_limits(&config, price.1)?;
+
+        if let Some(tolerance) = only_if_changed {
+            let state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+            if state.last_updated_time != 0
+                && !state.price.is_zero()
+                && price_change(state.price, price.1) <= tolerance
+            {
+                attributes.push(attr("skipped", price.0.to_string()));
+                continue;
+            }
+        }
+
+        let stored_price = update_price(
+            deps.storage,
+            &asset_token_raw,
+            price.1,
+            env.block.time.seconds(),
+            source.clone(),
+            (feeder_raw.clone(), authorization),
+        )?;
+        attributes.push(attr("asset", price.0.to_string()));
+        attributes.push(attr("price", stored_price.to_string()));
+
+        hook_messages.extend(price_hook_messages(
+            deps.as_ref(),
+            &price.0,
+            &asset_token_raw,
+            stored_price,
+            env.block.time.seconds(),
+        )?);
+    }
+
+    if let Some(alt_prices) = alt_prices {
+        let config: Config = read_config(deps.storage)?;
+        if config.alt_base_asset.is_none() {
+            return Err(ContractError::AltBaseAssetNotConfigured {});
+        }
+
+        for price in alt_prices {
+            attributes.push(attr("asset", price.0.to_string()));
+            attributes.push(attr("alt_price", price.1.to_string()));
+
+            // Check feeder permission
+            let asset_token_raw = deps.api.addr_canonicalize(&price.0)?;
+            assert_feeder_authorized(deps.storage, &config, &feeder_raw, &asset_token_raw)?;
+            assert_not_frozen(deps.storage, &asset_token_raw)?;
+            assert_price_within_limits(&config, price.1)?;
+
+            let mut state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+            state.alt_last_updated_time = env.block.time.seconds();
+            state.alt_price = Some(price.1);
+
+            store_price(deps.storage, &asset_token_raw, &state)?;
+
+            let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+            asset_config.action_count += 1;
+            store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(hook_messages)
+        .add_attributes(attributes))
+}
+
+/// Each component is authorized as in FeedPrice, the index price is derived
+/// from the stored component prices so it never lags a component update
+pub fn try_feed_basket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    index_token: String,
+    components: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    assert_not_in_maintenance(deps.storage, &env)?;
+    let feeder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if is_feeder_suspended(deps.storage, &feeder_raw)? {
+        return Err(ContractError::FeederSuspended {});
+    }
+    assert_feeder_allowlisted(deps.storage, &feeder_raw)?;
+    let config: Config = read_config(deps.storage)?;
+    assert_feed_rate_limit(deps.storage, &config, &feeder_raw, env.block.time.seconds())?;
+    let messages: Vec<CosmosMsg> = feed_fee_messages(deps.as_ref(), &config, &info)?;
+    let mut hook_messages: Vec<SubMsg> = vec![];
+
+    let index_token_raw = deps.api.addr_canonicalize(&index_token)?;
+    if read_feeder(deps.storage, &index_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+    assert_not_frozen(deps.storage, &index_token_raw)?;
+
+    let basket = read_asset_config(deps.storage, &index_token_raw)?.basket;
+    if basket.is_empty() {
+        return Err(ContractError::NotAnIndex {});
+    }
+
+    let components_raw = components
+        .iter()
+        .map(|(component, _)| deps.api.addr_canonicalize(component))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    if components_raw.len() != basket.len()
+        || basket
+            .iter()
+            .any(|(component_raw, _)| !components_raw.contains(component_raw))
+    {
+        return Err(ContractError::BasketMismatch {});
+    }
+
+    let now = env.block.time.seconds();
+    let mut attributes = vec![
+        attr("action", "feed_basket"),
+        attr("index_token", index_token.clone()),
+    ];
+    let mut index_price = Decimal::zero();
+    // the index is set by whoever set its components
+    let mut index_authorization = PriceAuthorization::Feeder;
+    for ((component, price), component_raw) in components.iter().zip(components_raw.iter()) {
+        let authorization =
+            assert_feeder_authorized(deps.storage, &config, &feeder_raw, component_raw)?;
+        assert_not_frozen(deps.storage, component_raw)?;
+        assert_price_within_limits(&config, *price)?;
+
+        let stored_price = update_price(
+            deps.storage,
+            component_raw,
+            *price,
+            now,
+            None,
+            (feeder_raw.clone(), authorization.clone()),
+        )?;
+        index_authorization = authorization;
+        attributes.push(attr("asset", component.to_string()));
+        attributes.push(attr("price", stored_price.to_string()));
+        hook_messages.extend(price_hook_messages(
+            deps.as_ref(),
+            component,
+            component_raw,
+            stored_price,
+            now,
+        )?);
+
+        let units = basket
+            .iter()
+            .find(|(raw, _)| raw == component_raw)
+            .map(|(_, units)| *units)
+            .unwrap_or_default();
+        index_price = index_price + decimal_multiplication(units, stored_price);
+    }
+
+    let stored_index_price = update_price(
+        deps.storage,
+        &index_token_raw,
+        index_price,
+        now,
+        None,
+        (feeder_raw, index_authorization),
+    )?;
+    attributes.push(attr("index_price", stored_index_price.to_string()));
+    hook_messages.extend(price_hook_messages(
+        deps.as_ref(),
+        &index_token,
+        &index_token_raw,
+        stored_index_price,
+        now,
+    )?);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(hook_messages)
+        .add_attributes(attributes))
+}
+
+/// The submitter pays the feed fee, as any relayer may submit the signature
+pub fn try_feed_price_signed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    price: Decimal,
+    nonce: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let feeder_pubkey = read_feeder_pubkey(deps.storage, &asset_token_raw)
+        .map_err(|_| ContractError::FeederPubkeyNotFound {})?;
+
+    assert_not_paused(deps.storage)?;
+    assert_not_frozen(deps.storage, &asset_token_raw)?;
+    assert_not_in_maintenance(deps.storage, &env)?;
+
+    // the signing key belongs to the asset feeder, so it is suspended along with it
+    let feeder_raw = read_feeder(deps.storage, &asset_token_raw)?;
+    if is_feeder_suspended(deps.storage, &feeder_raw)? {
+        return Err(ContractError::FeederSuspended {});
+    }
+    assert_feeder_allowlisted(deps.storage, &feeder_raw)?;
+    let config: Config = read_config(deps.storage)?;
+    // the per-asset feeder holds no feeding authority under a feeder group
+    if config.feeder_group.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_price_within_limits(&config, price)?;
+    assert_feed_rate_limit(deps.storage, &config, &feeder_raw, env.block.time.seconds())?;
+    let messages: Vec<CosmosMsg> = feed_fee_messages(deps.as_ref(), &config, &info)?;
+
+    if nonce <= read_feed_nonce(deps.storage, &asset_token_raw)? {
+        return Err(ContractError::NonceUsed {});
+    }
+
+    let sign_bytes =
+        feed_price_sign_bytes(env.contract.address.as_str(), &asset_token, price, nonce);
+    let message_hash = Sha256::digest(&sign_bytes);
+    let verified = deps
+        .api
+        .secp256k1_verify(&message_hash, &signature, &feeder_pubkey)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    store_feed_nonce(deps.storage, &asset_token_raw, nonce)?;
+    let price = update_price(
+        deps.storage,
+        &asset_token_raw,
+        price,
+        env.block.time.seconds(),
+        None,
+        (feeder_raw, PriceAuthorization::Feeder),
+    )?;
+    let hook_messages = price_hook_messages(
+        deps.as_ref(),
+        &asset_token,
+        &asset_token_raw,
+        price,
+        env.block.time.seconds(),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(hook_messages)
+        .add_attributes(vec![
+            attr("action", "price_feed_signed"),
+            attr("asset", asset_token),
+            attr("price", price.to_string()),
+            attr("nonce", nonce.to_string()),
+        ]))
+}
+
+/// Checks the sent funds cover the feed fee and forwards them to the fee collector
+fn feed_fee_messages(
+    deps: Deps,
+    config: &Config,
+    info: &MessageInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    match &config.feed_fee {
+        Some(feed_fee) => fee_messages(
+            deps,
+            config,
+            info,
+            feed_fee,
+            ContractError::InsufficientFeedFee {
+                fee: feed_fee.clone(),
+            },
+        ),
+        None => Ok(vec![]),
+    }
+}
+
+/// Forwards the sent funds of the fee denom to the fee collector, failing
+/// with `insufficient` when they do not cover the fee
+fn fee_messages(
+    deps: Deps,
+    config: &Config,
+    info: &MessageInfo,
+    fee: &Coin,
+    insufficient: ContractError,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let fee_collector = config
+        .fee_collector
+        .as_ref()
+        .ok_or(ContractError::FeeCollectorNotSet {})?;
+
+    let sent_amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == fee.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if sent_amount < fee.amount {
+        return Err(insufficient);
+    }
+
+    if sent_amount.is_zero() {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: deps.api.addr_humanize(fee_collector)?.to_string(),
+        amount: vec![Coin {
+            denom: fee.denom.clone(),
+            amount: sent_amount,
+        }],
+    })])
+}
+
+pub fn try_subscribe(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+) -> Result<Response, ContractError> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    let (fee, duration) = asset_config
+        .subscription
+        .clone()
+        .ok_or(ContractError::SubscriptionNotOffered {})?;
+    let config: Config = read_config(deps.storage)?;
+    let messages = fee_messages(
+        deps.as_ref(),
+        &config,
+        &info,
+        &fee,
+        ContractError::InsufficientSubscriptionFee { fee: fee.clone() },
+    )?;
+
+    // expired subscriptions are dropped here, until then they are only skipped
+    let now = env.block.time.seconds();
+    asset_config
+        .paid_subscribers
+        .retain(|(_, expires_at)| *expires_at > now);
+
+    let subscriber_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let expires_at = match asset_config
+        .paid_subscribers
+        .iter_mut()
+        .find(|(raw, _)| *raw == subscriber_raw)
+    {
+        Some((_, expires_at)) => {
+            *expires_at = expires_at.saturating_add(duration);
+            *expires_at
+        }
+        None => {
+            if asset_config.subscribers.len() + asset_config.paid_subscribers.len()
+                >= MAX_SUBSCRIBERS
+            {
+                return Err(ContractError::TooManySubscribers {
+                    max_subscribers: MAX_SUBSCRIBERS,
+                });
+            }
+            let expires_at = now.saturating_add(duration);
+            asset_config
+                .paid_subscribers
+                .push((subscriber_raw, expires_at));
+            expires_at
+        }
+    };
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "subscribe"),
+        attr("asset_token", asset_token),
+        attr("subscriber", info.sender.to_string()),
+        attr("expires_at", expires_at.to_string()),
+    ]))
+}
+
+/// Notifies the asset's subscribers and unexpired paid subscribers of its
+/// new base_asset price. A failing subscriber is replied to and ignored, so
+/// it can not revert the feed
+fn price_hook_messages(
+    deps: Deps,
+    asset_token: &str,
+    asset_token_raw: &CanonicalAddr,
+    price: Decimal,
+    time: u64,
+) -> StdResult<Vec<SubMsg>> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, asset_token_raw)?;
+    let price = effective_price(&config, &asset_config, price);
+    let paid_subscribers = asset_config
+        .paid_subscribers
+        .iter()
+        .filter(|(_, expires_at)| *expires_at > time)
+        .map(|(subscriber, _)| subscriber);
+    asset_config
+        .subscribers
+        .iter()
+        .chain(paid_subscribers)
+        .map(|subscriber| {
+            Ok(SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: deps.api.addr_humanize(subscriber)?.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&PriceHookMsg::PriceUpdate {
+                        asset_token: asset_token.to_string(),
+                        price,
+                        last_updated_time: time,
+                    })?,
+                }),
+                PRICE_HOOK_REPLY_ID,
+            )
+            .with_gas_limit(PRICE_HOOK_GAS_LIMIT))
+        })
+        .collect()
+}
+
+/// Bytes a feeder signs to authorize a FeedPriceSigned message. The oracle address
+/// is included so a signature cannot be replayed against another deployment
+pub fn feed_price_sign_bytes(
+    oracle: &str,
+    asset_token: &str,
+    price: Decimal,
+    nonce: u64,
+) -> Vec<u8> {
+    format!("{}:{}:{}:{}", oracle, asset_token, price, nonce).into_bytes()
+}
+
+/// Returns the change from `prev_price` to `price` relative to `prev_price`,
+/// which must not be zero
+fn price_change(prev_price: Decimal, price: Decimal) -> Decimal {
+    let change = if price > prev_price {
+        price - prev_price
+    } else {
+        prev_price - price
+    };
+
+    decimal_division(change, prev_price)
+}
+
+/// Stores a new base_asset price for the asset, updating its EMA, and returns
+/// the price stored after applying the asset's price band
+fn update_price(
+    storage: &mut dyn Storage,
+    asset_token_raw: &CanonicalAddr,
+    price: Decimal,
+    time: u64,
+    source: Option<String>,
+    set_by: (CanonicalAddr, PriceAuthorization),
+) -> Result<Decimal, ContractError> {
+    let mut state: PriceInfo = read_price(storage, asset_token_raw)?;
+    let mut asset_config: AssetConfig = read_asset_config(storage, asset_token_raw)?;
+
+    if let Some(max_deviation) = asset_config.max_deviation {
+        // the first feed has nothing to deviate from unless a reference is pinned
+        let reference_price = asset_config
+            .reference_price
+            .or_else(|| Some(state.price).filter(|price| !price.is_zero()));
+        if let Some(reference_price) = reference_price {
+            if price_change(reference_price, price) > max_deviation {
+                return Err(ContractError::PriceDeviationTooHigh {});
+            }
+        }
+    }
+
+    if let (Some(peg), Some(peg_tolerance)) = (asset_config.peg, asset_config.peg_tolerance) {
+        if price_change(peg, price) > peg_tolerance {
+            return Err(ContractError::PriceOffPeg {});
+        }
+    }
+
+    if asset_config.monotone_increasing && state.last_updated_time != 0 && price < state.price {
+        return Err(ContractError::PriceDecreased {});
+    }
+
+    let mut was_clamped = false;
+    let price = match asset_config.price_band {
+        Some((min_price, max_price)) if price < min_price || price > max_price => {
+            if asset_config.out_of_band == OutOfBand::Reject {
+                return Err(ContractError::PriceOutOfBand {});
+            }
+            was_clamped = true;
+            if price < min_price {
+                min_price
+            } else {
+                max_price
+            }
+        }
+        _ => price,
+    };
+
+    state.ema = match asset_config.ema_alpha {
+        // the first feed seeds the average, as does one after an unseeded EMA
+        Some(ema_alpha) if state.last_updated_time != 0 && !state.ema.is_zero() => {
+            decimal_multiplication(price, ema_alpha)
+                + decimal_multiplication(state.ema, Decimal::one() - ema_alpha)
+        }
+        _ => price,
+    };
+    // the first feed has no previous one to measure from
+    if state.last_updated_time != 0 {
+        state.total_update_interval += time.saturating_sub(state.last_updated_time);
+        state.update_interval_count += 1;
+    }
+    state.last_updated_time = time;
+    state.prev_price = state.price;
+    state.price = price;
+    state.source = source;
+    state.was_clamped = was_clamped;
+    state.update_count += 1;
+    state.set_by = Some(set_by);
+
+    store_price(storage, asset_token_raw, &state)?;
+    asset_config.action_count += 1;
+    store_asset_config(storage, asset_token_raw, &asset_config)?;
+    Ok(price)
+}
+
+pub fn try_reset_all_prices(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !config.allow_reset_all {
+        return Err(ContractError::ResetNotAllowed {});
+    }
+
+    let reset_count = reset_prices(deps.storage)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "reset_all_prices"),
+        attr("reset_count", reset_count.to_string()),
+    ]))
+}
+
+/// Subject to the same checks as FeedPrice, as it keeps the price fresh
+pub fn try_heartbeat(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    assert_not_in_maintenance(deps.storage, &env)?;
+    let feeder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if is_feeder_suspended(deps.storage, &feeder_raw)? {
+        return Err(ContractError::FeederSuspended {});
+    }
+    assert_feeder_allowlisted(deps.storage, &feeder_raw)?;
+    let config: Config = read_config(deps.storage)?;
+    assert_feed_rate_limit(deps.storage, &config, &feeder_raw, env.block.time.seconds())?;
+    let messages: Vec<CosmosMsg> = feed_fee_messages(deps.as_ref(), &config, &info)?;
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    assert_feeder_authorized(deps.storage, &config, &feeder_raw, &asset_token_raw)?;
+    assert_not_frozen(deps.storage, &asset_token_raw)?;
+
+    // a heartbeat must not make the zero price of a new asset look fresh
+    let mut state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    if state.last_updated_time == 0 {
+        return Err(ContractError::NeverFed {});
+    }
+    state.last_updated_time = env.block.time.seconds();
+    store_price(deps.storage, &asset_token_raw, &state)?;
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.action_count += 1;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "heartbeat"),
+        attr("asset_token", asset_token),
+        attr("last_updated_time", state.last_updated_time.to_string()),
+    ]))
+}
+
+pub fn try_set_last_update_time(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    time: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if time > env.block.time.seconds() {
+        return Err(ContractError::FutureTime {});
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    if read_feeder(deps.storage, &asset_token_raw).is_err() {
+        return Err(ContractError::AssetNotFound {});
+    }
+
+    let mut state: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    state.last_updated_time = time;
+    store_price(deps.storage, &asset_token_raw, &state)?;
+
+    let mut asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    asset_config.action_count += 1;
+    store_asset_config(deps.storage, &asset_token_raw, &asset_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_last_update_time"),
+        attr("asset_token", asset_token),
+        attr("time", time.to_string()),
+    ]))
+}
+
+pub fn try_import_prices(
+    deps: DepsMut,
+    info: MessageInfo,
+    source_oracle: String,
+    asset_tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let source_oracle = deps.api.addr_validate(&source_oracle)?;
+
+    let mut attributes = vec![
+        attr("action", "import_prices"),
+        attr("source_oracle", source_oracle.as_str()),
+    ];
+    for asset_token in asset_tokens {
+        let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+        if read_feeder(deps.storage, &asset_token_raw).is_err() {
+            return Err(ContractError::AssetNotFound {});
+        }
+
+        match query_source_price(
+            &deps.querier,
+            source_oracle.to_string(),
+            asset_token.clone(),
+            config.base_asset.clone(),
+        ) {
+            Some((price, last_updated_time)) => {
+                update_price(
+                    deps.storage,
+                    &asset_token_raw,
+                    price,
+                    last_updated_time,
+                    None,
+                    (config.owner.clone(), PriceAuthorization::Owner),
+                )?;
+                attributes.push(attr("imported", asset_token));
+            }
+            None => attributes.push(attr("skipped", asset_token)),
+        }
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Feeder { asset_token } => to_binary(&query_feeder(deps, asset_token)?),
+        QueryMsg::Price {
+            base_asset,
+            quote_asset,
+            base,
+        } => to_binary(&query_price(deps, env, base_asset, quote_asset, base)?),
+        QueryMsg::Prices {
+            start_after,
+            limit,
+            order_by,
+        } => to_binary(&query_prices(deps, start_after, limit, order_by)?),
+        QueryMsg::AllEffectivePrices {
+            start_after,
+            limit,
+            now,
+        } => to_binary(&query_all_effective_prices(deps, start_after, limit, now)?),
+        QueryMsg::Feeders { start_after, limit } => {
+            to_binary(&query_feeders(deps, start_after, limit)?)
+        }
+        QueryMsg::AssetExists { asset_token } => to_binary(&query_asset_exists(deps, asset_token)?),
+        QueryMsg::Overview {} => to_binary(&query_overview(deps)?),
+        QueryMsg::AssetTokensWithPrefix { prefix, limit } => {
+            to_binary(&query_asset_tokens_with_prefix(deps, prefix, limit)?)
+        }
+        QueryMsg::MostStale { now, limit } => to_binary(&query_most_stale(deps, now, limit)?),
+        QueryMsg::SafePrice { asset_token, now } => {
+            to_binary(&query_safe_price(deps, asset_token, now)?)
+        }
+        QueryMsg::PriceIfFresh {
+            asset_token,
+            now,
+            max_age,
+        } => to_binary(&query_price_if_fresh(deps, asset_token, now, max_age)?),
+        QueryMsg::ConfirmedPrice {
+            asset_token,
+            min_updates,
+        } => to_binary(&query_confirmed_price(deps, asset_token, min_updates)?),
+        QueryMsg::PathPrice { path, explain } => {
+            to_binary(&query_path_price(deps, env, path, explain)?)
+        }
+        QueryMsg::PriceInDenom { asset_token, via } => {
+            to_binary(&query_path_price(deps, env, vec![asset_token, via], None)?)
+        }
+        QueryMsg::MarketIndex {} => to_binary(&query_market_index(deps, env)?),
+        QueryMsg::UpdateTimeDiff { a, b } => to_binary(&query_update_time_diff(deps, a, b)?),
+        QueryMsg::PricesMap { asset_tokens } => to_binary(&query_prices_map(deps, asset_tokens)?),
+        QueryMsg::HealthScore { asset_token, now } => {
+            to_binary(&query_health_score(deps, asset_token, now)?)
+        }
+        QueryMsg::ExecutionPrice { asset_token, size } => {
+            to_binary(&query_execution_price(deps, asset_token, size)?)
+        }
+        QueryMsg::BigMovers { threshold } => to_binary(&query_big_movers(deps, threshold)?),
+        QueryMsg::Snapshot { label } => to_binary(&query_snapshot(deps, label)?),
+        QueryMsg::StaleAt { asset_token } => to_binary(&query_stale_at(deps, asset_token)?),
+        QueryMsg::CurrentPriceSource { asset_token } => {
+            to_binary(&query_current_price_source(deps, asset_token)?)
+        }
+        QueryMsg::RawConfig {} => to_binary(&query_raw_config(deps)?),
+        QueryMsg::PauseStatus {} => to_binary(&query_pause_status(deps)?),
+        QueryMsg::PriceRoot {} => to_binary(&query_price_root(deps)?),
+        QueryMsg::FullPrice {
+            asset_token,
+            explain,
+        } => to_binary(&query_full_price(deps, asset_token, explain)?),
+        QueryMsg::QuotedPrice {
+            base_asset,
+            quote_asset,
+            decimals,
+        } => to_binary(&query_quoted_price(
+            deps,
+            env,
+            base_asset,
+            quote_asset,
+            decimals,
+        )?),
+        QueryMsg::BatchPortfolioValue { portfolios } => {
+            to_binary(&query_batch_portfolio_value(deps, env, portfolios)?)
+        }
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let state = read_config(deps.storage)?;
+    let resp = ConfigResponse {
+        owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        base_asset: state.base_asset,
+        alt_base_asset: state.alt_base_asset,
+        price_validity_period: state.price_validity_period,
+        allow_reset_all: state.allow_reset_all,
+        default_feeder: state
+            .default_feeder
+            .map(|default_feeder| deps.api.addr_humanize(&default_feeder))
+            .transpose()?
+            .map(|default_feeder| default_feeder.to_string()),
+        max_assets: state.max_assets,
+        display_decimals: state.display_decimals,
+        global_feeder_allowlist: state
+            .global_feeder_allowlist
+            .iter()
+            .map(|feeder| Ok(deps.api.addr_humanize(feeder)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+        relay_contract: state
+            .relay_contract
+            .map(|relay_contract| deps.api.addr_humanize(&relay_contract))
+            .transpose()?
+            .map(|relay_contract| relay_contract.to_string()),
+        feed_fee: state.feed_fee,
+        fee_collector: state
+            .fee_collector
+            .map(|fee_collector| deps.api.addr_humanize(&fee_collector))
+            .transpose()?
+            .map(|fee_collector| fee_collector.to_string()),
+        on_stale: state.on_stale,
+        maintenance_window: state.maintenance_window,
+        price_limits: state.price_limits,
+        feeder_max_feeds_per_window: state.feeder_max_feeds_per_window,
+        frozen_query_policy: state.frozen_query_policy,
+        min_multiplier: state.min_multiplier,
+        max_multiplier: state.max_multiplier,
+        multiplier_enabled: state.multiplier_enabled,
+        post_unpause_grace: state.post_unpause_grace,
+        feeder_group: state
+            .feeder_group
+            .map(|feeder_group| deps.api.addr_humanize(&feeder_group))
+            .transpose()?
+            .map(|feeder_group| feeder_group.to_string()),
+    };
+
+    Ok(resp)
+}
+
+fn query_raw_config(deps: Deps) -> StdResult<RawConfigResponse> {
+    let state = read_config(deps.storage)?;
+    Ok(RawConfigResponse {
+        owner: state
+            .owner
+            .as_slice()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+        base_asset: state.base_asset,
+    })
+}
+
+fn query_snapshot(deps: Deps, label: String) -> StdResult<SnapshotResponse> {
+    let snapshot: Snapshot = read_snapshot(deps.storage, &label)?;
+    Ok(SnapshotResponse {
+        label,
+        taken_at: snapshot.taken_at,
+        prices: snapshot.prices,
+    })
+}
+
+/// Returns the sender of the base_asset price and how it was authorized
+fn humanize_set_by(
+    deps: Deps,
+    price_info: &PriceInfo,
+) -> StdResult<(Option<String>, Option<PriceAuthorization>)> {
+    match &price_info.set_by {
+        Some((set_by, authorization)) => Ok((
+            Some(deps.api.addr_humanize(set_by)?.to_string()),
+            Some(authorization.clone()),
+        )),
+        None => Ok((None, None)),
+    }
+}
+
+fn query_current_price_source(
+    deps: Deps,
+    asset_token: String,
+) -> StdResult<CurrentPriceSourceResponse> {
+    let price_info: PriceInfo =
+        read_price(deps.storage, &deps.api.addr_canonicalize(&asset_token)?)?;
+    let (set_by, authorization) = humanize_set_by(deps, &price_info)?;
+
+    Ok(CurrentPriceSourceResponse {
+        asset_token,
+        set_by,
+        authorization,
+    })
+}
+
+fn query_stale_at(deps: Deps, asset_token: String) -> StdResult<StaleAtResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let price_info: PriceInfo =
+        read_price(deps.storage, &deps.api.addr_canonicalize(&asset_token)?)?;
+
+    Ok(StaleAtResponse {
+        stale_at: if price_info.last_updated_time == 0 {
+            0
+        } else {
+            price_info
+                .last_updated_time
+                .saturating_add(config.price_validity_period)
+        },
+    })
+}
+
+fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    Ok(PauseStatusResponse {
+        paused: pause_info.paused,
+        reason: pause_info.reason,
+        since: pause_info.since,
+    })
+}
+
+fn query_feeder(deps: Deps, asset_token: String) -> StdResult<FeederResponse> {
+    let feeder = read_feeder(deps.storage, &deps.api.addr_canonicalize(&asset_token)?)?;
+    let resp = FeederResponse {
+        asset_token,
+        feeder: deps.api.addr_humanize(&feeder)?.to_string(),
+    };
+
+    Ok(resp)
+}
+
+fn query_price(
+    deps: Deps,
+    env: Env,
+    base: String,
+    quote: String,
+    denom: Option<String>,
+) -> StdResult<PriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    // when either side is the alt base asset, both sides are read in alt terms
+    let denom = match (denom, &config.alt_base_asset) {
+        (Some(denom), _) if denom == config.base_asset => denom,
+        (Some(denom), Some(alt_base_asset)) if denom == *alt_base_asset => denom,
+        (Some(denom), _) => {
+            return Err(StdError::generic_err(format!(
+                "{} is neither the base asset nor the alt base asset",
+                denom
+            )))
+        }
+        (None, Some(alt_base_asset)) if *alt_base_asset == base || *alt_base_asset == quote => {
+            alt_base_asset.clone()
+        }
+        (None, _) => config.base_asset.clone(),
+    };
+
+    let quote_price = load_price(deps, &config, &denom, &quote)?;
+    let base_price = load_price(deps, &config, &denom, &base)?;
+
+    let is_stale = |last_updated_time: u64| {
+        last_updated_time.saturating_add(config.price_validity_period) < env.block.time.seconds()
+    };
+    let base_stale = is_stale(base_price.last_updated_time);
+    let stale = base_stale || is_stale(quote_price.last_updated_time);
+    if stale && config.on_stale == StaleResponse::Error {
+        // the first stale leg with a custom message reports it
+        let stale_message = if base_stale {
+            base_price.stale_message.or(quote_price.stale_message)
+        } else {
+            quote_price.stale_message
+        };
+        return Err(StdError::generic_err(
+            stale_message.unwrap_or_else(|| "price too old".to_string()),
+        ));
+    }
+
+    let frozen = base_price.frozen || quote_price.frozen;
+    if frozen && config.frozen_query_policy == FrozenPolicy::Error {
+        return Err(StdError::generic_err("asset is frozen"));
+    }
+
+    // SetPaused(false) records when the oracle was unpaused
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    let warming_up = !pause_info.paused
+        && pause_info.since.saturating_add(config.post_unpause_grace) > env.block.time.seconds();
+
+    // a quote that was never fed has no rate, the response reports never_fed
+    let mut rate = if quote_price.price.is_zero() {
+        Decimal::zero()
+    } else {
+        decimal_division(base_price.price, quote_price.price)
+    };
+    // an unseeded quote EMA has no rate
+    let mut ema_rate = if quote_price.ema.is_zero() {
+        Decimal::zero()
+    } else {
+        decimal_division(base_price.ema, quote_price.ema)
+    };
+    let mut base_effective_price = base_price.price;
+    if let Some(display_decimals) = config.display_decimals {
+        rate = decimal_truncate(rate, display_decimals);
+        ema_rate = decimal_truncate(ema_rate, display_decimals);
+        base_effective_price = decimal_truncate(base_effective_price, display_decimals);
+    }
+
+    Ok(PriceResponse {
+        rate,
+        ema_rate,
+        last_updated_base: base_price.last_updated_time,
+        last_updated_quote: quote_price.last_updated_time,
+        source_base: base_price.source,
+        source_quote: quote_price.source,
+        frozen,
+        never_fed: base_price.last_updated_time == 0 || quote_price.last_updated_time == 0,
+        stale,
+        effective_price: base_effective_price,
+        warming_up,
+    })
+}
+
+fn query_big_movers(deps: Deps, threshold: Decimal) -> StdResult<BigMoversResponse> {
+    let asset_tokens: Vec<String> = read_all_prices(deps.storage)?
+        .into_iter()
+        .filter(|(_, price_info)| {
+            // assets fed at most once have nothing to compare against
+            !price_info.prev_price.is_zero()
+                && price_change(price_info.prev_price, price_info.price) > threshold
+        })
+        .map(|(asset_token_raw, _)| Ok(deps.api.addr_humanize(&asset_token_raw)?.to_string()))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    Ok(BigMoversResponse { asset_tokens })
+}
+
+fn query_price_root(deps: Deps) -> StdResult<PriceRootResponse> {
+    let mut leaves: Vec<(String, Vec<u8>)> = read_all_prices(deps.storage)?
+        .into_iter()
+        .map(|(asset_token_raw, price_info)| {
+            let asset_token = deps.api.addr_humanize(&asset_token_raw)?.to_string();
+            let leaf = format!(
+                "{},{},{}",
+                asset_token, price_info.price, price_info.last_updated_time
+            );
+            Ok((asset_token, Sha256::digest(leaf.as_bytes()).to_vec()))
+        })
+        .collect::<StdResult<Vec<(String, Vec<u8>)>>>()?;
+    leaves.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut level: Vec<Vec<u8>> = leaves.into_iter().map(|(_, leaf)| leaf).collect();
+    if level.is_empty() {
+        return Ok(PriceRootResponse {
+            root: Binary::from(vec![0u8; 32]),
+        });
+    }
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    Sha256::digest(&[left.as_slice(), right.as_slice()].concat()).to_vec()
+                }
+                [single] => single.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    Ok(PriceRootResponse {
+        root: Binary::from(level.remove(0)),
+    })
+}
+
+fn query_safe_price(deps: Deps, asset_token: String, now: u64) -> StdResult<SafePriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    if !is_fresh(&config, &price_info, now) {
+        return Err(stale_error(&asset_config, "price too old".to_string()));
+    }
+
+    Ok(SafePriceResponse {
+        price: effective_price(&config, &asset_config, price_info.price),
+        last_updated: price_info.last_updated_time,
+    })
+}
+
+fn query_price_if_fresh(
+    deps: Deps,
+    asset_token: String,
+    now: u64,
+    max_age: u64,
+) -> StdResult<SafePriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    if price_info.last_updated_time == 0
+        || now.saturating_sub(price_info.last_updated_time) > max_age
+    {
+        return Err(stale_error(&asset_config, "price too old".to_string()));
+    }
+
+    Ok(SafePriceResponse {
+        price: effective_price(&config, &asset_config, price_info.price),
+        last_updated: price_info.last_updated_time,
+    })
+}
+
+fn query_confirmed_price(
+    deps: Deps,
+    asset_token: String,
+    min_updates: u64,
+) -> StdResult<SafePriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    if price_info.update_count < min_updates {
+        return Err(StdError::generic_err(format!(
+            "price fed {} times, {} required",
+            price_info.update_count, min_updates
+        )));
+    }
+
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    Ok(SafePriceResponse {
+        price: effective_price(&config, &asset_config, price_info.price),
+        last_updated: price_info.last_updated_time,
+    })
+}
+
+fn query_path_price(
+    deps: Deps,
+    env: Env,
+    path: Vec<String>,
+    explain: Option<bool>,
+) -> StdResult<PathPriceResponse> {
+    if path.len() < 2 {
+        return Err(StdError::generic_err("path must have at least two assets"));
+    }
+
+    let config: Config = read_config(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    // base_asset prices of every asset on the path
+    let mut hops: Vec<(Decimal, u64)> = vec![];
+    for asset in path.iter() {
+        if *asset == config.base_asset {
+            hops.push((Decimal::one(), u64::MAX));
+            continue;
+        }
+
+        let asset_raw = deps.api.addr_canonicalize(asset)?;
+        let price_info: PriceInfo = read_price(deps.storage, &asset_raw)
+            .map_err(|_| StdError::generic_err(format!("No price for {}", asset)))?;
+        if price_info.price.is_zero() || !is_fresh(&config, &price_info, now) {
+            return Err(StdError::generic_err(format!(
+                "price too old for {}",
+                asset
+            )));
+        }
+        let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_raw)?;
+        hops.push((
+            effective_price(&config, &asset_config, price_info.price),
+            price_info.last_updated_time,
+        ));
+    }
+
+    let hop_rates: Vec<Decimal> = hops
+        .windows(2)
+        .map(|pair| decimal_division(pair[0].0, pair[1].0))
+        .collect();
+    let rate = hop_rates.iter().fold(Decimal::one(), |rate, hop_rate| {
+        decimal_multiplication(rate, *hop_rate)
+    });
+
+    let derivation = if explain.unwrap_or(false) {
+        Some(
+            path.windows(2)
+                .zip(hop_rates.iter())
+                .map(|(pair, hop_rate)| format!("{}/{} = {}", pair[0], pair[1], hop_rate))
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    Ok(PathPriceResponse {
+        rate,
+        last_updated: hops.iter().map(|hop| hop.1).min().unwrap_or_default(),
+        derivation,
+    })
+}
+
+fn query_market_index(deps: Deps, env: Env) -> StdResult<MarketIndexResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let prices: Vec<Decimal> = read_all_prices(deps.storage)?
+        .into_iter()
+        .filter(|(_, price_info)| {
+            !price_info.price.is_zero() && is_fresh(&config, price_info, env.block.time.seconds())
+        })
+        .map(|(asset_token_raw, price_info)| {
+            let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+            Ok(effective_price(&config, &asset_config, price_info.price))
+        })
+        .collect::<StdResult<Vec<Decimal>>>()?;
+
+    if prices.is_empty() {
+        return Ok(MarketIndexResponse {
+            average_price: Decimal::zero(),
+            asset_count: 0,
+        });
+    }
+
+    Ok(MarketIndexResponse {
+        average_price: checked_decimal_mean(&prices)?,
+        asset_count: prices.len() as u32,
+    })
+}
+
+fn query_update_time_diff(deps: Deps, a: String, b: String) -> StdResult<UpdateTimeDiffResponse> {
+    let last_updated_time = |asset_token: &str| -> StdResult<u64> {
+        let asset_token_raw = deps.api.addr_canonicalize(asset_token)?;
+        read_feeder(deps.storage, &asset_token_raw)
+            .map_err(|_| StdError::generic_err(format!("{} is not registered", asset_token)))?;
+        Ok(read_price(deps.storage, &asset_token_raw)?.last_updated_time)
+    };
+
+    let a_time = last_updated_time(&a)?;
+    let b_time = last_updated_time(&b)?;
+
+    Ok(UpdateTimeDiffResponse {
+        diff: a_time.abs_diff(b_time),
+        fresher: match a_time.cmp(&b_time) {
+            Ordering::Greater => Some(a),
+            Ordering::Less => Some(b),
+            Ordering::Equal => None,
+        },
+    })
+}
+
+fn query_prices_map(deps: Deps, asset_tokens: Vec<String>) -> StdResult<PricesMapResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let mut prices: BTreeMap<String, Decimal> = BTreeMap::new();
+    for asset_token in asset_tokens {
+        let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+        if let Ok(price_info) = read_price(deps.storage, &asset_token_raw) {
+            let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+            prices.insert(
+                asset_token,
+                effective_price(&config, &asset_config, price_info.price),
+            );
+        }
+    }
+
+    Ok(PricesMapResponse {
+        prices: prices.into_iter().collect(),
+    })
+}
+
+const MAX_STALENESS_PENALTY: u64 = 50;
+const MAX_DEVIATION_PENALTY: u128 = 50;
+// penalty points per unit of relative change, 5 points per 1%
+const DEVIATION_PENALTY_RATE: u128 = 500;
+
+fn query_health_score(deps: Deps, asset_token: String, now: u64) -> StdResult<HealthScoreResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let price_info: PriceInfo =
+        read_price(deps.storage, &deps.api.addr_canonicalize(&asset_token)?)?;
+    if price_info.last_updated_time == 0 {
+        return Ok(HealthScoreResponse { score: 0 });
+    }
+
+    let age = now.saturating_sub(price_info.last_updated_time);
+    let staleness_penalty = if age >= config.price_validity_period {
+        MAX_STALENESS_PENALTY
+    } else {
+        MAX_STALENESS_PENALTY * age / config.price_validity_period
+    };
+
+    // assets fed only once have no deviation to penalize
+    let deviation_penalty = if price_info.prev_price.is_zero() {
+        0
+    } else {
+        let change = price_change(price_info.prev_price, price_info.price);
+        (change * Uint128::from(DEVIATION_PENALTY_RATE))
+            .u128()
+            .min(MAX_DEVIATION_PENALTY)
+    };
+
+    Ok(HealthScoreResponse {
+        score: (100 - staleness_penalty - deviation_penalty as u64) as u8,
+    })
+}
+
+fn query_execution_price(
+    deps: Deps,
+    asset_token: String,
+    size: Uint128,
+) -> StdResult<ExecutionPriceResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+
+    let price = effective_price(&config, &asset_config, price_info.price);
+    let price = match asset_config.slippage_per_unit {
+        Some(slippage_per_unit) => checked_slippage_price(price, slippage_per_unit, size)?,
+        None => price,
+    };
+
+    Ok(ExecutionPriceResponse {
+        price,
+        last_updated: price_info.last_updated_time,
+    })
+}
+
+fn query_full_price(
+    deps: Deps,
+    asset_token: String,
+    explain: Option<bool>,
+) -> StdResult<FullPriceResponse> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let feeder = read_feeder(deps.storage, &asset_token_raw)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+
+    // component prices are read as stored now, which is what FeedBasket
+    // derived the index price from
+    let derivation = if explain.unwrap_or(false) {
+        Some(
+            asset_config
+                .basket
+                .iter()
+                .map(|(component_raw, units)| {
+                    let component_price = read_price(deps.storage, component_raw)?.price;
+                    Ok(format!(
+                        "{}: {} * {} = {}",
+                        deps.api.addr_humanize(component_raw)?,
+                        units,
+                        component_price,
+                        decimal_multiplication(*units, component_price)
+                    ))
+                })
+                .collect::<StdResult<Vec<String>>>()?,
+        )
+    } else {
+        None
+    };
+
+    let (set_by, authorization) = humanize_set_by(deps, &price_info)?;
+
+    Ok(FullPriceResponse {
+        asset_token,
+        feeder: deps.api.addr_humanize(&feeder)?.to_string(),
+        decimals: asset_config.decimals,
+        price: price_info.price,
+        ema: price_info.ema,
+        prev_price: price_info.prev_price,
+        last_updated_time: price_info.last_updated_time,
+        source: price_info.source,
+        alt_price: price_info.alt_price,
+        alt_last_updated_time: price_info.alt_last_updated_time,
+        avg_update_interval: price_info
+            .total_update_interval
+            .checked_div(price_info.update_interval_count),
+        frozen: asset_config.frozen,
+        pause_reason: asset_config.pause_reason,
+        was_clamped: price_info.was_clamped,
+        derivation,
+        action_count: asset_config.action_count,
+        update_count: price_info.update_count,
+        set_by,
+        authorization,
+    })
+}
+
+fn query_quoted_price(
+    deps: Deps,
+    env: Env,
+    base: String,
+    quote: String,
+    decimals: u8,
+) -> StdResult<QuotedPriceResponse> {
+    if decimals > MAX_QUOTED_DECIMALS {
+        return Err(StdError::generic_err(format!(
+            "decimals must be at most {}",
+            MAX_QUOTED_DECIMALS
+        )));
+    }
+
+    let price = query_price(deps, env, base, quote, None)?;
+
+    // multiplying a Uint128 by a Decimal truncates, so the rate is rounded down
+    Ok(QuotedPriceResponse {
+        rate: Uint128::from(10u128.pow(decimals as u32)) * price.rate,
+        last_updated_base: price.last_updated_base,
+        last_updated_quote: price.last_updated_quote,
+    })
+}
+
+/// A single leg of a price query, denominated in the requested denom
+struct LoadedPrice {
+    price: Decimal,
+    ema: Decimal,
+    last_updated_time: u64,
+    source: Option<String>,
+    frozen: bool,
+    stale_message: Option<String>,
+}
+
+/// Returns the price of the asset denominated in `denom`. EMA and source are
+/// only tracked for base_asset prices, so alt prices report the raw price as
+/// their EMA and no source
+fn load_price(deps: Deps, config: &Config, denom: &str, asset: &str) -> StdResult<LoadedPrice> {
+    if denom == asset {
+        return Ok(LoadedPrice {
+            price: Decimal::one(),
+            ema: Decimal::one(),
+            last_updated_time: u64::MAX,
+            source: None,
+            frozen: false,
+            stale_message: None,
+        });
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(asset)?;
+    let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+    let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+    let frozen = asset_config.frozen;
+    if denom == config.base_asset {
+        let multiplier = price_multiplier(config, &asset_config, price_info.price);
+        Ok(LoadedPrice {
+            price: decimal_multiplication(price_info.price, multiplier),
+            ema: decimal_multiplication(price_info.ema, multiplier),
+            last_updated_time: price_info.last_updated_time,
+            source: price_info.source,
+            frozen,
+            stale_message: asset_config.stale_message,
+        })
+    } else {
+        let alt_price = price_info
+            .alt_price
+            .ok_or_else(|| StdError::generic_err(format!("No {} price for {}", denom, asset)))?;
+        let alt_price = decimal_multiplication(
+            alt_price,
+            price_multiplier(config, &asset_config, alt_price),
+        );
+        Ok(LoadedPrice {
+            price: alt_price,
+            ema: alt_price,
+            last_updated_time: price_info.alt_last_updated_time,
+            source: None,
+            frozen,
+            stale_message: asset_config.stale_message,
+        })
+    }
+}
+
+fn query_prices(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<PricesResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(&start_after)?)
+    } else {
+        None
+    };
+
+    let prices: Vec<PricesResponseElem> = read_prices(deps, start_after, limit, order_by)?;
+
+    Ok(PricesResponse { prices })
+}
+
+fn query_all_effective_prices(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    now: u64,
+) -> StdResult<AllEffectivePricesResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(&start_after)?)
+    } else {
+        None
+    };
+
+    let prices = read_prices(deps, start_after, limit, Some(OrderBy::Asc))?
+        .into_iter()
+        .map(|elem| {
+            let asset_token_raw = deps.api.addr_canonicalize(&elem.asset_token)?;
+            let price_info: PriceInfo = read_price(deps.storage, &asset_token_raw)?;
+            let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+
+            Ok(EffectivePricesResponseElem {
+                asset_token: elem.asset_token,
+                effective_price: effective_price(&config, &asset_config, elem.price),
+                is_stale: !is_fresh(&config, &price_info, now),
+            })
+        })
+        .collect::<StdResult<Vec<EffectivePricesResponseElem>>>()?;
+
+    Ok(AllEffectivePricesResponse { prices })
+}
+
+/// Returns the price as the Price query quotes it in base_asset, with the
+/// asset's multiplier applied and truncated to the display decimals
+fn effective_price(config: &Config, asset_config: &AssetConfig, price: Decimal) -> Decimal {
+    let effective_price =
+        decimal_multiplication(price, price_multiplier(config, asset_config, price));
+    match config.display_decimals {
+        Some(display_decimals) => decimal_truncate(effective_price, display_decimals),
+        None => effective_price,
+    }
+}
+
+fn query_batch_portfolio_value(
+    deps: Deps,
+    env: Env,
+    portfolios: Vec<(String, Vec<(String, Uint128)>)>,
+) -> StdResult<BatchPortfolioValueResponse> {
+    let entries: usize = portfolios.iter().map(|(_, holdings)| holdings.len()).sum();
+    if entries > MAX_PORTFOLIO_ENTRIES {
+        return Err(StdError::generic_err(format!(
+            "at most {} portfolio entries can be valued at once",
+            MAX_PORTFOLIO_ENTRIES
+        )));
+    }
+
+    let config: Config = read_config(deps.storage)?;
+    let now = env.block.time.seconds();
+    let totals = portfolios
+        .into_iter()
+        .map(|(label, holdings)| {
+            let mut total = Uint128::zero();
+            for (asset_token, amount) in holdings {
+                let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+                let price_info: PriceInfo =
+                    read_price(deps.storage, &asset_token_raw).map_err(|_| {
+                        StdError::generic_err(format!("no price for asset {}", asset_token))
+                    })?;
+                let asset_config: AssetConfig = read_asset_config(deps.storage, &asset_token_raw)?;
+                if !is_fresh(&config, &price_info, now) {
+                    return Err(stale_error(
+                        &asset_config,
+                        format!("price of asset {} is stale", asset_token),
+                    ));
+                }
+
+                let value = checked_amount_multiplication(
+                    amount,
+                    effective_price(&config, &asset_config, price_info.price),
+                )?;
+                total = total
+                    .checked_add(value)
+                    .map_err(|_| StdError::generic_err("value overflow"))?;
+            }
+
+            Ok((label, total))
+        })
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+    Ok(BatchPortfolioValueResponse { totals })
+}
+
+fn query_feeders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FeedersResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(&start_after)?)
+    } else {
+        None
+    };
+
+    let feeders: Vec<FeedersResponseElem> = read_feeders(deps, start_after, limit)?;
+
+    Ok(FeedersResponse { feeders })
+}
+
+fn query_asset_exists(deps: Deps, asset_token: String) -> StdResult<AssetExistsResponse> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let exists = read_feeder(deps.storage, &asset_token_raw).is_ok();
+
+    Ok(AssetExistsResponse { exists })
+}
+
+fn query_overview(deps: Deps) -> StdResult<OverviewResponse> {
+    // read one past the limit to detect truncation
+    let mut asset_tokens = read_asset_tokens(deps, OVERVIEW_ASSET_LIMIT + 1)?;
+    let truncated = asset_tokens.len() > OVERVIEW_ASSET_LIMIT;
+    asset_tokens.truncate(OVERVIEW_ASSET_LIMIT);
+
+    Ok(OverviewResponse {
+        config: query_config(deps)?,
+        asset_tokens,
+        truncated,
+    })
+}
+
+fn query_asset_tokens_with_prefix(
+    deps: Deps,
+    prefix: String,
+    limit: u32,
+) -> StdResult<AssetTokensResponse> {
+    let asset_tokens = read_asset_tokens_with_prefix(deps, &prefix, limit)?;
+
+    Ok(AssetTokensResponse { asset_tokens })
+}
+
+fn query_most_stale(deps: Deps, now: u64, limit: u32) -> StdResult<MostStaleResponse> {
+    let limit = limit.min(MOST_STALE_MAX_LIMIT) as usize;
+
+    let mut assets: Vec<MostStaleResponseElem> = read_all_prices(deps.storage)?
+        .into_iter()
+        .map(|(asset_token_raw, price_info)| {
+            Ok(MostStaleResponseElem {
+                asset_token: deps.api.addr_humanize(&asset_token_raw)?.to_string(),
+                last_updated_time: price_info.last_updated_time,
+                age: now.saturating_sub(price_info.last_updated_time),
+            })
+        })
+        .collect::<StdResult<Vec<MostStaleResponseElem>>>()?;
+
+    // stable sort, so assets of equal age stay ordered by asset token
+    assets.sort_by_key(|asset| Reverse(asset.age));
+    assets.truncate(limit);
+
+    Ok(MostStaleResponse { assets })
+}
+
+/// Only failed price hooks are replied to, the failure is recorded and the
+/// feed that sent them goes through
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match (msg.id, msg.result) {
+        (PRICE_HOOK_REPLY_ID, ContractResult::Err(err)) => {
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "price_hook_failed"),
+                attr("error", err),
+            ]))
+        }
+        _ => Err(StdError::generic_err("unexpected reply")),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    migrate_config(deps.storage, msg.price_validity_period)?;
+    migrate_asset_count(deps.storage)?;
+    migrate_price_ema(deps.storage)?;
+
     Ok(Response::default())
 }
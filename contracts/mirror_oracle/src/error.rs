@@ -0,0 +1,185 @@
+use cosmwasm_std::{Coin, Decimal, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("nothing to update")]
+    NothingToUpdate {},
+
+    #[error("no scheduled config is effective yet")]
+    NoScheduledConfig {},
+
+    #[error("cannot register more than {max_assets} assets")]
+    TooManyAssets { max_assets: u64 },
+
+    #[error("display_decimals must be at most 18")]
+    InvalidDisplayDecimals {},
+
+    #[error("asset is not registered")]
+    AssetNotFound {},
+
+    #[error("asset is already registered")]
+    AssetAlreadyRegistered {},
+
+    #[error("invalid token address")]
+    InvalidTokenAddress {},
+
+    #[error("token is reserved for a base asset")]
+    ReservedToken {},
+
+    #[error("invalid feeder address")]
+    InvalidFeederAddress {},
+
+    #[error("no feeder provided and no default feeder configured")]
+    NoFeeder {},
+
+    #[error("invalid feeder pubkey")]
+    InvalidFeederPubkey {},
+
+    #[error("feeder pubkey is not registered")]
+    FeederPubkeyNotFound {},
+
+    #[error("alt_base_asset must be different from base_asset")]
+    InvalidAltBaseAsset {},
+
+    #[error("alt_base_asset is not configured")]
+    AltBaseAssetNotConfigured {},
+
+    #[error("ema_alpha must be within (0, 1]")]
+    InvalidEmaAlpha {},
+
+    #[error("source must be at most {max_length} bytes")]
+    SourceTooLong { max_length: usize },
+
+    #[error("pause_reason must be at most {max_length} bytes")]
+    PauseReasonTooLong { max_length: usize },
+
+    #[error("stale_message must be at most {max_length} bytes")]
+    StaleMessageTooLong { max_length: usize },
+
+    #[error("price is outside the asset's price band")]
+    PriceOutOfBand {},
+
+    #[error("price band minimum must not exceed its maximum")]
+    InvalidPriceBand {},
+
+    #[error("cannot have more than {max_subscribers} subscribers")]
+    TooManySubscribers { max_subscribers: usize },
+
+    #[error("asset has a fresh price, removing it requires force")]
+    FreshPrice {},
+
+    #[error("feeder is suspended")]
+    FeederSuspended {},
+
+    #[error("sent funds do not cover the feed fee of {fee}")]
+    InsufficientFeedFee { fee: Coin },
+
+    #[error("sent funds do not cover the subscription fee of {fee}")]
+    InsufficientSubscriptionFee { fee: Coin },
+
+    #[error("asset does not offer subscriptions")]
+    SubscriptionNotOffered {},
+
+    #[error("feed fee is set without a fee collector")]
+    FeeCollectorNotSet {},
+
+    #[error("feeds are rejected during the maintenance window")]
+    MaintenanceWindow {},
+
+    #[error("feeder is not on the global feeder allowlist")]
+    FeederNotAllowlisted {},
+
+    #[error("asset is frozen")]
+    AssetFrozen {},
+
+    #[error("oracle is paused")]
+    Paused {},
+
+    #[error("price is below the minimum of {min_price}")]
+    PriceBelowMin { min_price: Decimal },
+
+    #[error("price is above the maximum of {max_price}")]
+    PriceAboveMax { max_price: Decimal },
+
+    #[error("feeder exceeded its feed rate limit")]
+    FeedRateLimited {},
+
+    #[error("invalid price limits")]
+    InvalidPriceLimits {},
+
+    #[error("invalid price multiplier")]
+    InvalidMultiplier {},
+
+    #[error("invalid multiplier bounds")]
+    InvalidMultiplierBounds {},
+
+    #[error("price multiplier is outside the configured bounds")]
+    MultiplierOutOfBounds {},
+
+    #[error("price multipliers are disabled")]
+    MultiplierDisabled {},
+
+    #[error("asset {asset_token} is fed more than once")]
+    DuplicateAsset { asset_token: String },
+
+    #[error("price deviates too far from the reference price")]
+    PriceDeviationTooHigh {},
+
+    #[error("price can not decrease for a monotone increasing asset")]
+    PriceDecreased {},
+
+    #[error("price is outside the peg tolerance")]
+    PriceOffPeg {},
+
+    #[error("peg must be greater than zero")]
+    InvalidPeg {},
+
+    #[error("reference price must be greater than zero")]
+    InvalidReferencePrice {},
+
+    #[error("price must be greater than zero")]
+    ZeroPrice {},
+
+    #[error("invalid basket")]
+    InvalidBasket {},
+
+    #[error("asset is a component of an index basket")]
+    AssetInBasket {},
+
+    #[error("asset is not an index")]
+    NotAnIndex {},
+
+    #[error("components must match the index basket")]
+    BasketMismatch {},
+
+    #[error("asset has never been fed")]
+    NeverFed {},
+
+    #[error("time must not be in the future")]
+    FutureTime {},
+
+    #[error("nonce already used")]
+    NonceUsed {},
+
+    #[error("invalid signature")]
+    InvalidSignature {},
+
+    #[error("resetting prices is not allowed")]
+    ResetNotAllowed {},
+}
+
+impl From<ContractError> for StdError {
+    fn from(err: ContractError) -> Self {
+        match err {
+            ContractError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
@@ -1,6 +1,12 @@
 pub mod contract;
+pub mod error;
 pub mod math;
+pub mod migration;
+pub mod querier;
 pub mod state;
 
+#[cfg(test)]
+mod mock_querier;
+
 #[cfg(test)]
 mod tests;
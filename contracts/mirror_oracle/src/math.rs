@@ -1,8 +1,67 @@
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Decimal, Fraction, StdError, StdResult, Uint128, Uint256};
+use std::convert::TryFrom;
 
 const DECIMAL_FRACTIONAL: Uint128 = Uint128::new(1_000_000_000u128);
+const DECIMAL_ATOMICS: Uint128 = Uint128::new(1_000_000_000_000_000_000u128);
 
 /// return a / b
 pub fn decimal_division(a: Decimal, b: Decimal) -> Decimal {
     Decimal::from_ratio(DECIMAL_FRACTIONAL * a, b * DECIMAL_FRACTIONAL)
 }
+
+/// return a truncated to the given number of decimal places
+pub fn decimal_truncate(a: Decimal, decimals: u32) -> Decimal {
+    let fractional = Uint128::from(10u128.pow(decimals));
+    Decimal::from_ratio(fractional * a, fractional)
+}
+
+/// return a * b
+pub fn decimal_multiplication(a: Decimal, b: Decimal) -> Decimal {
+    Decimal::from_ratio(a * DECIMAL_FRACTIONAL * b, DECIMAL_FRACTIONAL)
+}
+
+/// return price * (1 + slippage_per_unit * size), erroring instead of
+/// panicking when an intermediate value overflows
+pub fn checked_slippage_price(
+    price: Decimal,
+    slippage_per_unit: Decimal,
+    size: Uint128,
+) -> StdResult<Decimal> {
+    let overflow = |_| StdError::generic_err("value overflow");
+
+    let multiplier = Uint128::from(slippage_per_unit.numerator())
+        .checked_mul(size)
+        .map_err(overflow)?
+        .checked_add(DECIMAL_ATOMICS)
+        .map_err(overflow)?;
+    let price =
+        Uint128::from(price.numerator()).full_mul(multiplier) / Uint256::from(DECIMAL_ATOMICS);
+
+    Ok(Decimal::from_ratio(
+        Uint128::try_from(price).map_err(|_| StdError::generic_err("value overflow"))?,
+        DECIMAL_ATOMICS,
+    ))
+}
+
+/// return the arithmetic mean of values, erroring instead of panicking when
+/// their sum overflows
+pub fn checked_decimal_mean(values: &[Decimal]) -> StdResult<Decimal> {
+    let mut sum = Uint128::zero();
+    for value in values {
+        sum = sum
+            .checked_add(Uint128::from(value.numerator()))
+            .map_err(|_| StdError::generic_err("value overflow"))?;
+    }
+
+    Ok(Decimal::from_ratio(
+        sum,
+        DECIMAL_ATOMICS * Uint128::from(values.len() as u128),
+    ))
+}
+
+/// return amount * price rounded down, erroring instead of panicking when it
+/// overflows
+pub fn checked_amount_multiplication(amount: Uint128, price: Decimal) -> StdResult<Uint128> {
+    let value = amount.full_mul(Uint128::from(price.numerator())) / Uint256::from(DECIMAL_ATOMICS);
+    Uint128::try_from(value).map_err(|_| StdError::generic_err("value overflow"))
+}
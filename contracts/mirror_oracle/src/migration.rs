@@ -0,0 +1,74 @@
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+
+use crate::state::{
+    count_assets, read_all_prices, store_asset_count, store_price, Config, KEY_CONFIG,
+};
+use mirror_protocol::oracle::{FrozenPolicy, StaleResponse};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: CanonicalAddr,
+    pub base_asset: String,
+}
+
+/// Converts a config stored before price_validity_period existed, a current
+/// config fails the conversion check and is left untouched
+pub fn migrate_config(storage: &mut dyn Storage, price_validity_period: u64) -> StdResult<()> {
+    let current_store: ReadonlySingleton<Config> = singleton_read(storage, KEY_CONFIG);
+    if current_store.load().is_ok() {
+        return Ok(());
+    }
+
+    let legacy_store: ReadonlySingleton<LegacyConfig> = singleton_read(storage, KEY_CONFIG);
+    let legacy_config: LegacyConfig = legacy_store.load()?;
+    let config = Config {
+        owner: legacy_config.owner,
+        base_asset: legacy_config.base_asset,
+        alt_base_asset: None,
+        price_validity_period,
+        allow_reset_all: false,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: vec![],
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: StaleResponse::Flag,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: FrozenPolicy::ReturnLast,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: true,
+        post_unpause_grace: 0,
+        feeder_group: None,
+    };
+    let mut store: Singleton<Config> = singleton(storage, KEY_CONFIG);
+    store.save(&config)?;
+    Ok(())
+}
+
+/// Assets registered before the asset count was tracked are counted once
+pub fn migrate_asset_count(storage: &mut dyn Storage) -> StdResult<()> {
+    let asset_count = count_assets(storage);
+    store_asset_count(storage, asset_count)
+}
+
+/// Prices stored before the EMA was tracked load with a zero EMA, which
+/// is seeded from the price instead
+pub fn migrate_price_ema(storage: &mut dyn Storage) -> StdResult<()> {
+    for (asset_token, mut price_info) in read_all_prices(storage)? {
+        if price_info.ema.is_zero() && !price_info.price.is_zero() {
+            price_info.ema = price_info.price;
+            store_price(storage, &asset_token, &price_info)?;
+        }
+    }
+
+    Ok(())
+}
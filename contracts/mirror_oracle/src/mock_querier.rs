@@ -0,0 +1,107 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, ContractResult, Decimal, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw20::TokenInfoResponse;
+use mirror_protocol::oracle::PriceResponse;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    oracle_prices: HashMap<String, (Decimal, u64)>,
+    token_decimals: u8,
+}
+
+pub fn mock_dependencies_with_querier() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: WasmMockQuerier::new(MockQuerier::new(&[])),
+    }
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockQueryMsg {
+    Price { base_asset: String },
+    TokenInfo {},
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => match from_binary(msg).unwrap() {
+                MockQueryMsg::Price { base_asset } => match self.oracle_prices.get(&base_asset) {
+                    Some((rate, last_updated_base)) => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
+                            rate: *rate,
+                            ema_rate: *rate,
+                            last_updated_base: *last_updated_base,
+                            last_updated_quote: u64::MAX,
+                            source_base: None,
+                            source_quote: None,
+                            frozen: false,
+                            never_fed: false,
+                            stale: false,
+                            effective_price: *rate,
+                            warming_up: false,
+                        })))
+                    }
+                    None => {
+                        SystemResult::Ok(ContractResult::Err("No oracle price exists".to_string()))
+                    }
+                },
+                MockQueryMsg::TokenInfo {} => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&TokenInfoResponse {
+                        name: "mirror asset".to_string(),
+                        symbol: "mASSET".to_string(),
+                        decimals: self.token_decimals,
+                        total_supply: Uint128::zero(),
+                    })))
+                }
+            },
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            oracle_prices: HashMap::new(),
+            token_decimals: 6u8,
+        }
+    }
+
+    pub fn with_token_decimals(&mut self, token_decimals: u8) {
+        self.token_decimals = token_decimals;
+    }
+
+    pub fn with_oracle_price(&mut self, oracle_prices: &[(&String, &(Decimal, u64))]) {
+        self.oracle_prices = oracle_prices
+            .iter()
+            .map(|(asset_token, price)| (asset_token.to_string(), **price))
+            .collect();
+    }
+}
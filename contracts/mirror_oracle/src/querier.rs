@@ -0,0 +1,46 @@
+use cosmwasm_std::{to_binary, Decimal, QuerierWrapper, QueryRequest, StdResult, WasmQuery};
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
+use serde::Deserialize;
+
+use mirror_protocol::oracle::QueryMsg;
+
+/// Fields of the Price query response shared by every oracle version
+#[derive(Deserialize)]
+struct SourcePriceResponse {
+    rate: Decimal,
+    last_updated_base: u64,
+}
+
+/// Returns the base_asset price and last updated time of the asset on the
+/// source oracle, None when the source oracle can not price it
+pub fn query_source_price(
+    querier: &QuerierWrapper,
+    source_oracle: String,
+    asset_token: String,
+    base_asset: String,
+) -> Option<(Decimal, u64)> {
+    let msg = to_binary(&QueryMsg::Price {
+        base_asset: asset_token,
+        quote_asset: base_asset,
+        base: None,
+    })
+    .ok()?;
+
+    let res: SourcePriceResponse = querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: source_oracle,
+            msg,
+        }))
+        .ok()?;
+
+    Some((res.rate, res.last_updated_base))
+}
+
+pub fn query_token_decimals(querier: &QuerierWrapper, token: String) -> StdResult<u8> {
+    let res: TokenInfoResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: token,
+        msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
+    }))?;
+
+    Ok(res.decimals)
+}
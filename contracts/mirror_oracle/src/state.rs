@@ -1,21 +1,82 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Decimal, Deps, StdResult, Storage};
+use cosmwasm_std::{Binary, CanonicalAddr, Coin, Decimal, Deps, Order, StdResult, Storage};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 
 use mirror_protocol::common::OrderBy;
-use mirror_protocol::oracle::PricesResponseElem;
+use mirror_protocol::oracle::{
+    ConfigChanges, FeedersResponseElem, FrozenPolicy, OutOfBand, PriceAuthorization,
+    PricesResponseElem, StaleResponse,
+};
+use std::collections::BTreeMap;
+use std::ops::Bound;
 
 static PREFIX_FEEDER: &[u8] = b"feeder";
 static PREFIX_PRICE: &[u8] = b"price";
+static PREFIX_ASSET_CONFIG: &[u8] = b"asset_config";
+static PREFIX_FEEDER_PUBKEY: &[u8] = b"feeder_pubkey";
+static PREFIX_FEED_NONCE: &[u8] = b"feed_nonce";
+static PREFIX_IDEMPOTENCY_KEY: &[u8] = b"idempotency_key";
+static PREFIX_SUSPENDED_FEEDER: &[u8] = b"suspended_feeder";
+static PREFIX_FEEDER_FEED_TIMES: &[u8] = b"feeder_feed_times";
+static PREFIX_SNAPSHOT: &[u8] = b"snapshot";
 
-static KEY_CONFIG: &[u8] = b"config";
+pub static KEY_CONFIG: &[u8] = b"config";
+static KEY_ASSET_COUNT: &[u8] = b"asset_count";
+static KEY_SCHEDULED_CONFIG: &[u8] = b"scheduled_config";
+static KEY_PAUSE_INFO: &[u8] = b"pause_info";
+static KEY_SNAPSHOT_LABELS: &[u8] = b"snapshot_labels";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
     pub base_asset: String,
+    pub alt_base_asset: Option<String>,
+    pub price_validity_period: u64,
+    #[serde(default)]
+    pub allow_reset_all: bool,
+    pub default_feeder: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub max_assets: Option<u64>,
+    #[serde(default)]
+    pub display_decimals: Option<u32>,
+    #[serde(default)]
+    pub global_feeder_allowlist: Vec<CanonicalAddr>,
+    #[serde(default)]
+    pub relay_contract: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub feed_fee: Option<Coin>,
+    #[serde(default)]
+    pub fee_collector: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub on_stale: StaleResponse,
+    #[serde(default)]
+    pub maintenance_window: Option<(u64, u64)>,
+    #[serde(default)]
+    pub price_limits: Option<(Decimal, Decimal)>,
+    #[serde(default)]
+    pub feeder_max_feeds_per_window: Option<(u32, u64)>,
+    #[serde(default)]
+    pub frozen_query_policy: FrozenPolicy,
+    #[serde(default)]
+    pub min_multiplier: Option<Decimal>,
+    #[serde(default)]
+    pub max_multiplier: Option<Decimal>,
+    /// when false, prices are quoted unscaled and only a multiplier of one
+    /// can be set
+    #[serde(default = "default_multiplier_enabled")]
+    pub multiplier_enabled: bool,
+    #[serde(default)]
+    pub post_unpause_grace: u64,
+    /// when set, the only feeder of every asset; per-asset feeders are kept
+    /// so that migrating back restores them
+    #[serde(default)]
+    pub feeder_group: Option<CanonicalAddr>,
+}
+
+fn default_multiplier_enabled() -> bool {
+    true
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -26,6 +87,96 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledConfig {
+    pub changes: ConfigChanges,
+    pub effective_at: u64,
+}
+
+pub fn store_scheduled_config(
+    storage: &mut dyn Storage,
+    scheduled_config: &ScheduledConfig,
+) -> StdResult<()> {
+    singleton(storage, KEY_SCHEDULED_CONFIG).save(scheduled_config)
+}
+
+pub fn read_scheduled_config(storage: &dyn Storage) -> StdResult<Option<ScheduledConfig>> {
+    singleton_read(storage, KEY_SCHEDULED_CONFIG).may_load()
+}
+
+pub fn remove_scheduled_config(storage: &mut dyn Storage) {
+    singleton::<ScheduledConfig>(storage, KEY_SCHEDULED_CONFIG).remove()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseInfo {
+    pub paused: bool,
+    pub reason: Option<String>,
+    pub since: u64,
+}
+
+pub fn store_pause_info(storage: &mut dyn Storage, pause_info: &PauseInfo) -> StdResult<()> {
+    singleton(storage, KEY_PAUSE_INFO).save(pause_info)
+}
+
+pub fn read_pause_info(storage: &dyn Storage) -> StdResult<PauseInfo> {
+    Ok(singleton_read(storage, KEY_PAUSE_INFO)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Snapshot {
+    pub taken_at: u64,
+    pub prices: Vec<PricesResponseElem>,
+}
+
+pub fn store_snapshot(
+    storage: &mut dyn Storage,
+    label: &str,
+    snapshot: &Snapshot,
+) -> StdResult<()> {
+    let mut snapshot_bucket: Bucket<Snapshot> = Bucket::new(storage, PREFIX_SNAPSHOT);
+    snapshot_bucket.save(label.as_bytes(), snapshot)
+}
+
+pub fn read_snapshot(storage: &dyn Storage, label: &str) -> StdResult<Snapshot> {
+    let snapshot_bucket: ReadonlyBucket<Snapshot> = ReadonlyBucket::new(storage, PREFIX_SNAPSHOT);
+    snapshot_bucket.load(label.as_bytes())
+}
+
+pub fn remove_snapshot(storage: &mut dyn Storage, label: &str) {
+    let mut snapshot_bucket: Bucket<Snapshot> = Bucket::new(storage, PREFIX_SNAPSHOT);
+    snapshot_bucket.remove(label.as_bytes())
+}
+
+/// Snapshot labels, oldest first
+pub fn store_snapshot_labels(storage: &mut dyn Storage, labels: &[String]) -> StdResult<()> {
+    singleton(storage, KEY_SNAPSHOT_LABELS).save(&labels.to_vec())
+}
+
+pub fn read_snapshot_labels(storage: &dyn Storage) -> StdResult<Vec<String>> {
+    Ok(singleton_read(storage, KEY_SNAPSHOT_LABELS)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn store_asset_count(storage: &mut dyn Storage, asset_count: u64) -> StdResult<()> {
+    singleton(storage, KEY_ASSET_COUNT).save(&asset_count)
+}
+
+pub fn read_asset_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_ASSET_COUNT)
+        .may_load()?
+        .unwrap_or(0u64))
+}
+
+/// Counts the registered assets by iterating over all of them
+pub fn count_assets(storage: &dyn Storage) -> u64 {
+    let feeder_bucket: ReadonlyBucket<CanonicalAddr> = ReadonlyBucket::new(storage, PREFIX_FEEDER);
+    feeder_bucket.range(None, None, Order::Ascending).count() as u64
+}
+
 pub fn store_feeder(
     storage: &mut dyn Storage,
     asset_token: &CanonicalAddr,
@@ -41,10 +192,184 @@ pub fn read_feeder(storage: &dyn Storage, asset_token: &CanonicalAddr) -> StdRes
     feeder_bucket.load(asset_token.as_slice())
 }
 
+pub fn remove_feeder(storage: &mut dyn Storage, asset_token: &CanonicalAddr) {
+    let mut feeder_bucket: Bucket<CanonicalAddr> = Bucket::new(storage, PREFIX_FEEDER);
+    feeder_bucket.remove(asset_token.as_slice())
+}
+
+pub fn store_feeder_pubkey(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    pubkey: &Binary,
+) -> StdResult<()> {
+    let mut pubkey_bucket: Bucket<Binary> = Bucket::new(storage, PREFIX_FEEDER_PUBKEY);
+    pubkey_bucket.save(asset_token.as_slice(), pubkey)
+}
+
+pub fn remove_feeder_pubkey(storage: &mut dyn Storage, asset_token: &CanonicalAddr) {
+    let mut pubkey_bucket: Bucket<Binary> = Bucket::new(storage, PREFIX_FEEDER_PUBKEY);
+    pubkey_bucket.remove(asset_token.as_slice())
+}
+
+pub fn store_feeder_suspended(
+    storage: &mut dyn Storage,
+    feeder: &CanonicalAddr,
+    suspended: bool,
+) -> StdResult<()> {
+    let mut suspended_bucket: Bucket<bool> = Bucket::new(storage, PREFIX_SUSPENDED_FEEDER);
+    if suspended {
+        suspended_bucket.save(feeder.as_slice(), &true)
+    } else {
+        suspended_bucket.remove(feeder.as_slice());
+        Ok(())
+    }
+}
+
+pub fn is_feeder_suspended(storage: &dyn Storage, feeder: &CanonicalAddr) -> StdResult<bool> {
+    let suspended_bucket: ReadonlyBucket<bool> =
+        ReadonlyBucket::new(storage, PREFIX_SUSPENDED_FEEDER);
+    Ok(suspended_bucket
+        .may_load(feeder.as_slice())?
+        .unwrap_or(false))
+}
+
+pub fn read_feeder_pubkey(storage: &dyn Storage, asset_token: &CanonicalAddr) -> StdResult<Binary> {
+    let pubkey_bucket: ReadonlyBucket<Binary> = ReadonlyBucket::new(storage, PREFIX_FEEDER_PUBKEY);
+    pubkey_bucket.load(asset_token.as_slice())
+}
+
+pub fn store_feed_nonce(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    nonce: u64,
+) -> StdResult<()> {
+    let mut nonce_bucket: Bucket<u64> = Bucket::new(storage, PREFIX_FEED_NONCE);
+    nonce_bucket.save(asset_token.as_slice(), &nonce)
+}
+
+/// Returns the last nonce used by a signed feed, zero if none was used yet
+pub fn read_feed_nonce(storage: &dyn Storage, asset_token: &CanonicalAddr) -> StdResult<u64> {
+    let nonce_bucket: ReadonlyBucket<u64> = ReadonlyBucket::new(storage, PREFIX_FEED_NONCE);
+    Ok(nonce_bucket
+        .may_load(asset_token.as_slice())?
+        .unwrap_or(0u64))
+}
+
+pub fn store_feeder_feed_times(
+    storage: &mut dyn Storage,
+    feeder: &CanonicalAddr,
+    feed_times: &[u64],
+) -> StdResult<()> {
+    let mut feed_times_bucket: Bucket<Vec<u64>> = Bucket::new(storage, PREFIX_FEEDER_FEED_TIMES);
+    feed_times_bucket.save(feeder.as_slice(), &feed_times.to_vec())
+}
+
+pub fn read_feeder_feed_times(
+    storage: &dyn Storage,
+    feeder: &CanonicalAddr,
+) -> StdResult<Vec<u64>> {
+    let feed_times_bucket: ReadonlyBucket<Vec<u64>> =
+        ReadonlyBucket::new(storage, PREFIX_FEEDER_FEED_TIMES);
+    Ok(feed_times_bucket
+        .may_load(feeder.as_slice())?
+        .unwrap_or_default())
+}
+
+pub fn store_idempotency_key(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    feeder: &CanonicalAddr,
+    idempotency_key: &str,
+) -> StdResult<()> {
+    let mut key_bucket: Bucket<String> =
+        Bucket::multilevel(storage, &[PREFIX_IDEMPOTENCY_KEY, asset_token.as_slice()]);
+    key_bucket.save(feeder.as_slice(), &idempotency_key.to_string())
+}
+
+/// Returns the last idempotency key the feeder submitted for the asset
+pub fn read_idempotency_key(
+    storage: &dyn Storage,
+    asset_token: &CanonicalAddr,
+    feeder: &CanonicalAddr,
+) -> StdResult<Option<String>> {
+    let key_bucket: ReadonlyBucket<String> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_IDEMPOTENCY_KEY, asset_token.as_slice()]);
+    key_bucket.may_load(feeder.as_slice())
+}
+
+pub fn read_asset_tokens(deps: Deps, limit: usize) -> StdResult<Vec<String>> {
+    let feeder_bucket: ReadonlyBucket<CanonicalAddr> =
+        ReadonlyBucket::new(deps.storage, PREFIX_FEEDER);
+
+    feeder_bucket
+        .range(None, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(deps.api.addr_humanize(&CanonicalAddr::from(k))?.to_string())
+        })
+        .collect()
+}
+
+/// Keys are canonical addresses, which do not sort like the human readable
+/// asset tokens, so every asset is scanned and matched against the prefix
+pub fn read_asset_tokens_with_prefix(
+    deps: Deps,
+    prefix: &str,
+    limit: u32,
+) -> StdResult<Vec<String>> {
+    let limit = limit.min(MAX_LIMIT) as usize;
+    let feeder_bucket: ReadonlyBucket<CanonicalAddr> =
+        ReadonlyBucket::new(deps.storage, PREFIX_FEEDER);
+
+    let mut asset_tokens: Vec<String> = feeder_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(deps.api.addr_humanize(&CanonicalAddr::from(k))?.to_string())
+        })
+        .collect::<StdResult<Vec<String>>>()?
+        .into_iter()
+        .filter(|asset_token| asset_token.starts_with(prefix))
+        .collect();
+
+    asset_tokens.sort();
+    asset_tokens.truncate(limit);
+    Ok(asset_tokens)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceInfo {
     pub price: Decimal,
     pub last_updated_time: u64,
+    /// price quoted in the alt_base_asset, if fed
+    pub alt_price: Option<Decimal>,
+    #[serde(default)]
+    pub alt_last_updated_time: u64,
+    /// exponential moving average of the base_asset price
+    #[serde(default)]
+    pub ema: Decimal,
+    /// venue the base_asset price was sourced from, as reported by the feeder
+    #[serde(default)]
+    pub source: Option<String>,
+    /// base_asset price replaced by the latest feed
+    #[serde(default)]
+    pub prev_price: Decimal,
+    /// seconds elapsed between consecutive feeds, summed
+    #[serde(default)]
+    pub total_update_interval: u64,
+    /// number of intervals summed into total_update_interval
+    #[serde(default)]
+    pub update_interval_count: u64,
+    /// whether price was clamped to the asset's price band
+    #[serde(default)]
+    pub was_clamped: bool,
+    /// number of base_asset feeds since registration or the last reset
+    #[serde(default)]
+    pub update_count: u64,
+    /// sender of the base_asset price and how it was authorized
+    #[serde(default)]
+    pub set_by: Option<(CanonicalAddr, PriceAuthorization)>,
 }
 
 pub fn store_price(
@@ -56,11 +381,170 @@ pub fn store_price(
     price_bucket.save(asset_token.as_slice(), price)
 }
 
+pub fn remove_price(storage: &mut dyn Storage, asset_token: &CanonicalAddr) {
+    let mut price_bucket: Bucket<PriceInfo> = Bucket::new(storage, PREFIX_PRICE);
+    price_bucket.remove(asset_token.as_slice())
+}
+
 pub fn read_price(storage: &dyn Storage, asset_token: &CanonicalAddr) -> StdResult<PriceInfo> {
     let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(storage, PREFIX_PRICE);
     price_bucket.load(asset_token.as_slice())
 }
 
+/// Reads the price of every registered asset, ordered by asset token.
+/// The scan is unbounded, so callers must keep the result size in check
+pub fn read_all_prices(storage: &dyn Storage) -> StdResult<Vec<(CanonicalAddr, PriceInfo)>> {
+    let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(storage, PREFIX_PRICE);
+    price_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
+/// Clears the alt price of every registered asset, they are quoted in a
+/// denom that is no longer the alt base asset
+pub fn clear_alt_prices(storage: &mut dyn Storage) -> StdResult<()> {
+    let prices = read_all_prices(storage)?;
+
+    let mut price_bucket: Bucket<PriceInfo> = Bucket::new(storage, PREFIX_PRICE);
+    for (asset_token, mut price_info) in prices.into_iter() {
+        if price_info.alt_price.is_none() {
+            continue;
+        }
+        price_info.alt_price = None;
+        price_info.alt_last_updated_time = 0u64;
+        price_bucket.save(asset_token.as_slice(), &price_info)?;
+    }
+
+    Ok(())
+}
+
+/// Clears the price of every registered asset, returning the number of assets reset
+pub fn reset_prices(storage: &mut dyn Storage) -> StdResult<usize> {
+    let prices = read_all_prices(storage)?;
+
+    let mut price_bucket: Bucket<PriceInfo> = Bucket::new(storage, PREFIX_PRICE);
+    for (asset_token, mut price_info) in prices.iter().cloned() {
+        price_info.price = Decimal::zero();
+        price_info.last_updated_time = 0u64;
+        price_info.alt_price = None;
+        price_info.alt_last_updated_time = 0u64;
+        price_info.ema = Decimal::zero();
+        price_info.source = None;
+        price_info.prev_price = Decimal::zero();
+        price_info.total_update_interval = 0u64;
+        price_info.update_interval_count = 0u64;
+        price_info.was_clamped = false;
+        price_info.update_count = 0u64;
+        price_info.set_by = None;
+        price_bucket.save(asset_token.as_slice(), &price_info)?;
+    }
+
+    Ok(prices.len())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct AssetConfig {
+    pub ema_alpha: Option<Decimal>,
+    pub slippage_per_unit: Option<Decimal>,
+    #[serde(default)]
+    pub frozen: bool,
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    #[serde(default)]
+    pub subscribers: Vec<CanonicalAddr>,
+    /// why the asset was frozen, cleared on unfreeze
+    #[serde(default)]
+    pub pause_reason: Option<String>,
+    #[serde(default)]
+    pub price_band: Option<(Decimal, Decimal)>,
+    #[serde(default)]
+    pub out_of_band: OutOfBand,
+    /// (component asset_token, units), empty unless an index asset
+    #[serde(default)]
+    pub basket: Vec<(CanonicalAddr, Decimal)>,
+    #[serde(default)]
+    pub price_multiplier: Option<Decimal>,
+    /// sorted by ascending threshold
+    #[serde(default)]
+    pub multiplier_tiers: Vec<(Decimal, Decimal)>,
+    #[serde(default)]
+    pub max_deviation: Option<Decimal>,
+    #[serde(default)]
+    pub reference_price: Option<Decimal>,
+    #[serde(default)]
+    pub monotone_increasing: bool,
+    #[serde(default)]
+    pub peg: Option<Decimal>,
+    #[serde(default)]
+    pub peg_tolerance: Option<Decimal>,
+    #[serde(default)]
+    pub stale_message: Option<String>,
+    /// (fee, duration) of a paid subscription, none when not offered
+    #[serde(default)]
+    pub subscription: Option<(Coin, u64)>,
+    /// (subscriber, expiry time) of paid subscriptions, notified along with
+    /// the subscribers until expiry
+    #[serde(default)]
+    pub paid_subscribers: Vec<(CanonicalAddr, u64)>,
+    /// feeds, heartbeats and owner changes applied to the asset, never reset
+    #[serde(default)]
+    pub action_count: u64,
+}
+
+pub fn store_asset_config(
+    storage: &mut dyn Storage,
+    asset_token: &CanonicalAddr,
+    asset_config: &AssetConfig,
+) -> StdResult<()> {
+    let mut asset_config_bucket: Bucket<AssetConfig> = Bucket::new(storage, PREFIX_ASSET_CONFIG);
+    asset_config_bucket.save(asset_token.as_slice(), asset_config)
+}
+
+pub fn remove_asset_config(storage: &mut dyn Storage, asset_token: &CanonicalAddr) {
+    let mut asset_config_bucket: Bucket<AssetConfig> = Bucket::new(storage, PREFIX_ASSET_CONFIG);
+    asset_config_bucket.remove(asset_token.as_slice())
+}
+
+/// Assets without stored settings use the default config
+pub fn read_asset_config(
+    storage: &dyn Storage,
+    asset_token: &CanonicalAddr,
+) -> StdResult<AssetConfig> {
+    let asset_config_bucket: ReadonlyBucket<AssetConfig> =
+        ReadonlyBucket::new(storage, PREFIX_ASSET_CONFIG);
+    Ok(asset_config_bucket
+        .may_load(asset_token.as_slice())?
+        .unwrap_or_default())
+}
+
+/// Index assets whose basket includes `component`
+pub fn read_indexes_with_component(
+    storage: &dyn Storage,
+    component: &CanonicalAddr,
+) -> StdResult<Vec<(CanonicalAddr, AssetConfig)>> {
+    let asset_config_bucket: ReadonlyBucket<AssetConfig> =
+        ReadonlyBucket::new(storage, PREFIX_ASSET_CONFIG);
+    asset_config_bucket
+        .range(None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((k, asset_config))
+                if asset_config
+                    .basket
+                    .iter()
+                    .any(|(component_raw, _)| component_raw == component) =>
+            {
+                Some(Ok((CanonicalAddr::from(k), asset_config)))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
@@ -94,6 +578,43 @@ pub fn read_prices(
         .collect()
 }
 
+/// Feeders are not indexed, so all assets are scanned to group them by feeder.
+/// Pages are ordered by the canonical feeder address
+pub fn read_feeders(
+    deps: Deps,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<FeedersResponseElem>> {
+    let feeder_bucket: ReadonlyBucket<CanonicalAddr> =
+        ReadonlyBucket::new(deps.storage, PREFIX_FEEDER);
+
+    let mut asset_counts: BTreeMap<Vec<u8>, u32> = BTreeMap::new();
+    for item in feeder_bucket.range(None, None, Order::Ascending) {
+        let (_, feeder) = item?;
+        *asset_counts.entry(feeder.as_slice().to_vec()).or_insert(0) += 1;
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start: Bound<Vec<u8>> = match start_after {
+        Some(start_after) => Bound::Excluded(start_after.as_slice().to_vec()),
+        None => Bound::Unbounded,
+    };
+
+    asset_counts
+        .range((start, Bound::Unbounded))
+        .take(limit)
+        .map(|(feeder, asset_count)| {
+            Ok(FeedersResponseElem {
+                feeder: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(feeder.clone()))?
+                    .to_string(),
+                asset_count: *asset_count,
+            })
+        })
+        .collect()
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
 fn calc_range_start(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
     start_after.map(|idx| {
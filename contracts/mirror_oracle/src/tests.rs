@@ -1,11 +1,32 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{
+    execute, feed_price_sign_bytes, instantiate, migrate, query, reply, PRICE_HOOK_GAS_LIMIT,
+    PRICE_HOOK_REPLY_ID,
+};
+use crate::error::ContractError;
+use crate::migration::LegacyConfig;
+use crate::mock_querier::mock_dependencies_with_querier;
+use crate::state::{read_price, store_price, store_scheduled_config, ScheduledConfig, KEY_CONFIG};
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, Decimal, StdError};
+use cosmwasm_std::{
+    attr, coin, from_binary, from_slice, to_binary, Api, BankMsg, Binary, ContractResult,
+    CosmosMsg, Decimal, Reply, StdError, SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cosmwasm_storage::{singleton, Singleton};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
 use mirror_protocol::common::OrderBy;
 use mirror_protocol::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    AllEffectivePricesResponse, AssetExistsResponse, AssetTokensResponse,
+    BatchPortfolioValueResponse, BigMoversResponse, ConfigChanges, ConfigResponse,
+    CurrentPriceSourceResponse, EffectivePricesResponseElem, ExecuteMsg, ExecutionPriceResponse,
+    FeederResponse, FeedersResponse, FeedersResponseElem, FrozenPolicy, FullPriceResponse,
+    HealthScoreResponse, InstantiateMsg, MarketIndexResponse, MigrateMsg, MostStaleResponse,
+    MostStaleResponseElem, OutOfBand, OverviewResponse, PathPriceResponse, PauseStatusResponse,
+    PriceAuthorization, PriceHookMsg, PriceResponse, PriceRootResponse, PricesMapResponse,
+    PricesResponse, PricesResponseElem, QueryMsg, QuotedPriceResponse, RawConfigResponse,
+    SafePriceResponse, SnapshotResponse, StaleAtResponse, StaleResponse, UpdateTimeDiffResponse,
 };
+use sha2::{Digest, Sha256};
 
 #[test]
 fn proper_initialization() {
@@ -14,6 +35,8 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -27,6 +50,10 @@ fn proper_initialization() {
     let config: ConfigResponse = from_binary(&res).unwrap();
     assert_eq!("owner0000", config.owner);
     assert_eq!("base0000", config.base_asset);
+    assert_eq!(None, config.alt_base_asset);
+    assert_eq!(60u64, config.price_validity_period);
+    assert!(!config.allow_reset_all);
+    assert_eq!(None, config.default_feeder);
 }
 
 #[test]
@@ -36,6 +63,8 @@ fn update_config() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -45,6 +74,24 @@ fn update_config() {
     let info = mock_info("owner0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
         owner: Some("owner0001".to_string()),
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -59,13 +106,100 @@ fn update_config() {
 
     // Unauthorized err
     let info = mock_info("owner0000", &[]);
-    let msg = ExecuteMsg::UpdateConfig { owner: None };
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
 
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // the owner is told when nothing would change
+    let info = mock_info("owner0001", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
-        _ => panic!("Must return unauthorized error"),
-    }
+    assert_eq!(res, Err(ContractError::NothingToUpdate {}));
+}
+
+#[test]
+fn update_config_clear() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_config = |feeder: &str, max_assets: u64, display_decimals: u32, fee: u128| {
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            alt_base_asset: None,
+            price_validity_period: None,
+            default_feeder: Some(feeder.to_string()),
+            max_assets: Some(max_assets),
+            display_decimals: Some(display_decimals),
+            global_feeder_allowlist: None,
+            relay_contract: Some(feeder.to_string()),
+            feed_fee: Some(coin(fee, "uusd")),
+            fee_collector: Some(feeder.to_string()),
+            on_stale: None,
+            maintenance_window: None,
+            price_limits: None,
+            feeder_max_feeds_per_window: None,
+            frozen_query_policy: None,
+            min_multiplier: None,
+            max_multiplier: None,
+            multiplier_enabled: None,
+            post_unpause_grace: None,
+        }
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let msg = update_config("addr0000", 10u64, 6u32, 100u128);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.default_feeder, Some("addr0000".to_string()));
+    assert_eq!(config.max_assets, Some(10u64));
+    assert_eq!(config.display_decimals, Some(6u32));
+    assert_eq!(config.relay_contract, Some("addr0000".to_string()));
+    assert_eq!(config.feed_fee, Some(coin(100u128, "uusd")));
+    assert_eq!(config.fee_collector, Some("addr0000".to_string()));
+
+    // empty addresses, zero caps and fees and full precision remove each setting
+    let msg = update_config("", 0u64, 18u32, 0u128);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.default_feeder, None);
+    assert_eq!(config.max_assets, None);
+    assert_eq!(config.display_decimals, None);
+    assert_eq!(config.relay_contract, None);
+    assert_eq!(config.feed_fee, None);
+    assert_eq!(config.fee_collector, None);
 }
 
 #[test]
@@ -74,6 +208,8 @@ fn update_price() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -82,19 +218,22 @@ fn update_price() {
     // register asset
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
-        feeder: "addr0000".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    match res {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "unauthorized"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
+    assert_eq!(res, ContractError::Unauthorized {});
 
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
-        feeder: "addr0001".to_string(),
+        feeder: Some("addr0001".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -103,7 +242,10 @@ fn update_price() {
     // try update an asset already exists
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
-        feeder: "addr0000".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -112,6 +254,10 @@ fn update_price() {
     // update price
     let msg = ExecuteMsg::FeedPrice {
         prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -125,6 +271,7 @@ fn update_price() {
         QueryMsg::Price {
             base_asset: "mAAPL".to_string(),
             quote_asset: "base0000".to_string(),
+            base: None,
         },
     )
     .unwrap();
@@ -135,13 +282,14 @@ fn update_price() {
     let info = mock_info("addr0001", &[]);
     let msg = ExecuteMsg::FeedPrice {
         prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    match res {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "unauthorized"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
+    assert_eq!(res, ContractError::Unauthorized {});
 }
 
 #[test]
@@ -151,6 +299,8 @@ fn feed_price() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -160,32 +310,42 @@ fn feed_price() {
     let info = mock_info("addr0000", &[]);
     let msg = ExecuteMsg::FeedPrice {
         prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
     };
 
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
 
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
-        feeder: "addr0000".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    match res {
-        StdError::GenericErr { msg, .. } => assert_eq!(msg, "unauthorized"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
+    assert_eq!(res, ContractError::Unauthorized {});
 
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mAAPL".to_string(),
-        feeder: "addr0000".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("owner0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     let msg = ExecuteMsg::RegisterAsset {
         asset_token: "mGOGL".to_string(),
-        feeder: "addr0000".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -215,6 +375,7 @@ fn feed_price() {
         QueryMsg::Price {
             base_asset: "mAAPL".to_string(),
             quote_asset: "base0000".to_string(),
+            base: None,
         },
     )
     .unwrap();
@@ -224,8 +385,16 @@ fn feed_price() {
         price_res,
         PriceResponse {
             rate: Decimal::zero(),
+            ema_rate: Decimal::zero(),
             last_updated_base: 0u64,
             last_updated_quote: u64::MAX,
+            source_base: None,
+            source_quote: None,
+            frozen: false,
+            never_fed: true,
+            stale: true,
+            effective_price: Decimal::zero(),
+            warming_up: false,
         }
     );
 
@@ -234,6 +403,10 @@ fn feed_price() {
             ("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128)),
             ("mGOGL".to_string(), Decimal::from_ratio(22u128, 10u128)),
         ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
     };
     let info = mock_info("addr0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -245,6 +418,7 @@ fn feed_price() {
         QueryMsg::Price {
             base_asset: "mAAPL".to_string(),
             quote_asset: "base0000".to_string(),
+            base: None,
         },
     )
     .unwrap();
@@ -254,8 +428,16 @@ fn feed_price() {
         price_res,
         PriceResponse {
             rate: Decimal::from_ratio(12u128, 10u128),
+            ema_rate: Decimal::from_ratio(12u128, 10u128),
             last_updated_base: env.block.time.seconds(),
             last_updated_quote: u64::MAX,
+            source_base: None,
+            source_quote: None,
+            frozen: false,
+            never_fed: false,
+            stale: false,
+            effective_price: Decimal::from_ratio(12u128, 10u128),
+            warming_up: false,
         }
     );
 
@@ -294,11 +476,7549 @@ fn feed_price() {
     let info = mock_info("addr0001", &[]);
     let msg = ExecuteMsg::FeedPrice {
         prices: vec![("mAAPL".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
-        _ => panic!("Must return unauthorized error"),
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+}
+
+#[test]
+fn asset_exists() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetExists {
+            asset_token: "maapl".to_string(),
+        },
+    )
+    .unwrap();
+    let exists_res: AssetExistsResponse = from_binary(&res).unwrap();
+    assert_eq!(exists_res, AssetExistsResponse { exists: true });
+
+    // unknown asset is not an error
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetExists {
+            asset_token: "mgogl".to_string(),
+        },
+    )
+    .unwrap();
+    let exists_res: AssetExistsResponse = from_binary(&res).unwrap();
+    assert_eq!(exists_res, AssetExistsResponse { exists: false });
+}
+
+#[test]
+fn feed_alt_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // alt prices are rejected until an alt base asset is configured
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: Some(vec![(
+            "maapl".to_string(),
+            Decimal::from_ratio(3u128, 1u128),
+        )]),
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::AltBaseAssetNotConfigured {});
+
+    let update_msg = |alt_base_asset: &str| ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: Some(alt_base_asset.to_string()),
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, update_msg("uluna")).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "uusd".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::from_ratio(150u128, 1u128),
+            ema_rate: Decimal::from_ratio(150u128, 1u128),
+            last_updated_base: env.block.time.seconds(),
+            last_updated_quote: u64::MAX,
+            source_base: None,
+            source_quote: None,
+            frozen: false,
+            never_fed: false,
+            stale: false,
+            effective_price: Decimal::from_ratio(150u128, 1u128),
+            warming_up: false,
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "uluna".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::from_ratio(3u128, 1u128),
+            ema_rate: Decimal::from_ratio(3u128, 1u128),
+            last_updated_base: env.block.time.seconds(),
+            last_updated_quote: u64::MAX,
+            source_base: None,
+            source_quote: None,
+            frozen: false,
+            never_fed: false,
+            stale: false,
+            effective_price: Decimal::from_ratio(3u128, 1u128),
+            warming_up: false,
+        }
+    );
+
+    // uluna prices are not reported as ukrw prices after a switch
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, update_msg("ukrw")).unwrap();
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "ukrw".to_string(),
+            base: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("No ukrw price for maapl"));
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.alt_price, None);
+    assert_eq!(full_price_res.alt_last_updated_time, 0u64);
+    assert_eq!(full_price_res.price, Decimal::from_ratio(150u128, 1u128));
+}
+
+#[test]
+fn price_in_requested_base() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: Some("uluna".to_string()),
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the alt prices imply a different cross rate than the base prices
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(300u128, 1u128)),
+        ],
+        alt_prices: Some(vec![
+            ("maapl".to_string(), Decimal::from_ratio(3u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(5u128, 1u128)),
+        ]),
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let query_rate = |base: Option<&str>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "mgogl".to_string(),
+                base: base.map(|base| base.to_string()),
+            },
+        )
+        .map(|res| from_binary::<PriceResponse>(&res).unwrap().rate)
+    };
+
+    assert_eq!(query_rate(None).unwrap(), Decimal::percent(50));
+    assert_eq!(query_rate(Some("uusd")).unwrap(), Decimal::percent(50));
+    assert_eq!(query_rate(Some("uluna")).unwrap(), Decimal::percent(60));
+    assert_eq!(
+        query_rate(Some("ukrw")).unwrap_err(),
+        StdError::generic_err("ukrw is neither the base asset nor the alt base asset")
+    );
+}
+
+#[test]
+fn price_unfed_quote() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let env = mock_env();
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "mgogl".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::zero(),
+            ema_rate: Decimal::zero(),
+            last_updated_base: env.block.time.seconds(),
+            last_updated_quote: 0u64,
+            source_base: None,
+            source_quote: None,
+            frozen: false,
+            never_fed: true,
+            stale: true,
+            effective_price: Decimal::from_ratio(150u128, 1u128),
+            warming_up: false,
+        }
+    );
+}
+
+#[test]
+fn price_ema() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // alpha out of range
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: Some(Decimal::zero()),
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidEmaAlpha {});
+
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: Some(Decimal::percent(50)),
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // first feed seeds the EMA, then each feed moves it halfway to the new price
+    let expected = [(100u128, 100u128), (200u128, 150u128), (50u128, 100u128)];
+    for (price, ema) in expected.iter() {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(*price, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "base0000".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(price_res.rate, Decimal::from_ratio(*price, 1u128));
+        assert_eq!(price_res.ema_rate, Decimal::from_ratio(*ema, 1u128));
+    }
+}
+
+#[test]
+fn reset_all_prices() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // disabled by default
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ResetAllPrices {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::ResetNotAllowed {});
+
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: Some(true),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(12u128, 10u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(22u128, 10u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can reset
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ResetAllPrices {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ResetAllPrices {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "reset_all_prices"), attr("reset_count", "2")]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Prices {
+            start_after: None,
+            limit: None,
+            order_by: Some(OrderBy::Asc),
+        },
+    )
+    .unwrap();
+    let prices_res: PricesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        prices_res,
+        PricesResponse {
+            prices: vec![
+                PricesResponseElem {
+                    asset_token: "maapl".to_string(),
+                    price: Decimal::zero(),
+                    last_updated_time: 0u64,
+                },
+                PricesResponseElem {
+                    asset_token: "mgogl".to_string(),
+                    price: Decimal::zero(),
+                    last_updated_time: 0u64,
+                }
+            ],
+        }
+    );
+}
+
+#[test]
+fn query_overview() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for i in 0..100 {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: format!("asset{:04}", i),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Overview {}).unwrap();
+    let overview_res: OverviewResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        overview_res.config,
+        ConfigResponse {
+            owner: "owner0000".to_string(),
+            base_asset: "base0000".to_string(),
+            alt_base_asset: None,
+            price_validity_period: 60u64,
+            allow_reset_all: false,
+            default_feeder: None,
+            max_assets: None,
+            display_decimals: None,
+            global_feeder_allowlist: vec![],
+            relay_contract: None,
+            feed_fee: None,
+            fee_collector: None,
+            on_stale: StaleResponse::Flag,
+            maintenance_window: None,
+            price_limits: None,
+            feeder_max_feeds_per_window: None,
+            frozen_query_policy: FrozenPolicy::ReturnLast,
+            min_multiplier: None,
+            max_multiplier: None,
+            multiplier_enabled: true,
+            post_unpause_grace: 0,
+            feeder_group: None,
+        }
+    );
+    assert_eq!(overview_res.asset_tokens.len(), 100);
+    assert_eq!(overview_res.asset_tokens[0], "asset0000");
+    assert_eq!(overview_res.asset_tokens[99], "asset0099");
+    assert!(!overview_res.truncated);
+
+    // one past the cap
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "asset0100".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Overview {}).unwrap();
+    let overview_res: OverviewResponse = from_binary(&res).unwrap();
+    assert_eq!(overview_res.asset_tokens.len(), 100);
+    assert!(overview_res.truncated);
+}
+
+#[test]
+fn feed_price_signed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let feeder_pubkey = signing_key.verifying_key().to_bytes().to_vec();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: Some(Binary::from(feeder_pubkey)),
+        decimals: None,
+        fetch_decimals: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let price = Decimal::from_ratio(12u128, 10u128);
+    let sign = |price: Decimal, nonce: u64| -> Binary {
+        let sign_bytes =
+            feed_price_sign_bytes(env.contract.address.as_str(), "maapl", price, nonce);
+        let signature: Signature = signing_key.sign(&sign_bytes);
+        Binary::from(signature.as_ref())
+    };
+
+    // any relayer can submit a valid signature
+    let msg = ExecuteMsg::FeedPriceSigned {
+        asset_token: "maapl".to_string(),
+        price,
+        nonce: 1u64,
+        signature: sign(price, 1u64),
+    };
+    let info = mock_info("relayer0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "base0000".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, price);
+    assert_eq!(price_res.last_updated_base, env.block.time.seconds());
+
+    // replaying the same nonce is rejected
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::NonceUsed {});
+
+    // tampered price
+    let msg = ExecuteMsg::FeedPriceSigned {
+        asset_token: "maapl".to_string(),
+        price: Decimal::from_ratio(13u128, 10u128),
+        nonce: 2u64,
+        signature: sign(price, 2u64),
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidSignature {});
+}
+
+#[test]
+fn remove_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // only the owner can remove
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "mgogl".to_string(),
+        force: false,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    // never fed asset is removed without force
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::AssetExists {
+            asset_token: "mgogl".to_string(),
+        },
+    )
+    .unwrap();
+    let exists_res: AssetExistsResponse = from_binary(&res).unwrap();
+    assert!(!exists_res.exists);
+
+    // fresh asset requires force
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::FreshPrice {});
+
+    let force_msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: true,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), force_msg).unwrap();
+
+    // stale asset is removed without force
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(12u128, 10u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let feeder_info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), feeder_info, msg).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(61);
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: false,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "base0000".to_string(),
+            base: None,
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn register_asset_with_default_feeder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: None,
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::NoFeeder {});
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: Some("keeper0000".to_string()),
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), update_msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(Some("keeper0000".to_string()), config.default_feeder);
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // explicit feeder overrides the default
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mgogl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, feeder) in [("maapl", "keeper0000"), ("mgogl", "addr0000")].iter() {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Feeder {
+                asset_token: asset_token.to_string(),
+            },
+        )
+        .unwrap();
+        let feeder_res: FeederResponse = from_binary(&res).unwrap();
+        assert_eq!(feeder_res.feeder, feeder.to_string());
+    }
+}
+
+#[test]
+fn query_most_stale() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // fed at 100, 300 and 200 respectively
+    let mut env = mock_env();
+    for (asset_token, time) in [
+        ("masset0", 100u64),
+        ("masset1", 300u64),
+        ("masset2", 200u64),
+    ]
+    .iter()
+    {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(*time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(asset_token.to_string(), Decimal::one())],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::MostStale {
+            now: 400u64,
+            limit: 2u32,
+        },
+    )
+    .unwrap();
+    let most_stale_res: MostStaleResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        most_stale_res,
+        MostStaleResponse {
+            assets: vec![
+                MostStaleResponseElem {
+                    asset_token: "masset0".to_string(),
+                    last_updated_time: 100u64,
+                    age: 300u64,
+                },
+                MostStaleResponseElem {
+                    asset_token: "masset2".to_string(),
+                    last_updated_time: 200u64,
+                    age: 200u64,
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn feed_price_with_source() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: Some("x".repeat(65)),
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::SourceTooLong { max_length: 64 });
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: Some("nasdaq".to_string()),
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.attributes[1], attr("source", "nasdaq"));
+
+    let query_msg = QueryMsg::Price {
+        base_asset: "maapl".to_string(),
+        quote_asset: "uusd".to_string(),
+        base: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.source_base, Some("nasdaq".to_string()));
+    assert_eq!(price_res.source_quote, None);
+
+    // feeding without a source clears the previous one
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.source_base, None);
+}
+
+#[test]
+fn feed_price_idempotency_key() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed_msg = |price: u128, idempotency_key: &str| ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: Some(idempotency_key.to_string()),
+        only_if_changed: None,
+    };
+    let query_rate = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        price_res.rate
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        feed_msg(100, "key0"),
+    )
+    .unwrap();
+    assert_eq!(query_rate(&deps), Decimal::from_ratio(100u128, 1u128));
+
+    // a retry with the same key is a no-op, even if the payload differs
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        feed_msg(200, "key0"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "price_feed"),
+            attr("idempotency_key", "key0"),
+            attr("duplicate", "true"),
+        ]
+    );
+    assert_eq!(query_rate(&deps), Decimal::from_ratio(100u128, 1u128));
+
+    // a fresh key applies normally
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        feed_msg(200, "key1"),
+    )
+    .unwrap();
+    assert_eq!(query_rate(&deps), Decimal::from_ratio(200u128, 1u128));
+
+    // a replaced feeder cannot replay its last key
+    let msg = ExecuteMsg::UpdateFeeders {
+        updates: vec![("maapl".to_string(), "addr0001".to_string())],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), info, feed_msg(300, "key1")).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn contract_error_into_std_error() {
+    // typed errors keep their messages when surfaced as a StdError
+    assert_eq!(
+        StdError::from(ContractError::Unauthorized {}),
+        StdError::generic_err("unauthorized")
+    );
+    assert_eq!(
+        StdError::from(ContractError::SourceTooLong { max_length: 64 }),
+        StdError::generic_err("source must be at most 64 bytes")
+    );
+
+    // wrapped StdErrors are passed through untouched
+    assert_eq!(
+        StdError::from(ContractError::Std(StdError::not_found("price"))),
+        StdError::not_found("price")
+    );
+}
+
+#[test]
+fn query_quoted_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![(
+            "maapl".to_string(),
+            Decimal::from_ratio(150_123_456u128, 1_000_000u128),
+        )],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_msg = |decimals: u8| QueryMsg::QuotedPrice {
+        base_asset: "maapl".to_string(),
+        quote_asset: "uusd".to_string(),
+        decimals,
+    };
+
+    let res = query(deps.as_ref(), mock_env(), query_msg(6)).unwrap();
+    let quoted_res: QuotedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        quoted_res,
+        QuotedPriceResponse {
+            rate: Uint128::from(150_123_456u128),
+            last_updated_base: mock_env().block.time.seconds(),
+            last_updated_quote: u64::MAX,
+        }
+    );
+
+    // 150.123456 is rounded down to 150.12
+    let res = query(deps.as_ref(), mock_env(), query_msg(2)).unwrap();
+    let quoted_res: QuotedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(quoted_res.rate, Uint128::from(15_012u128));
+
+    let res = query(deps.as_ref(), mock_env(), query_msg(0)).unwrap();
+    let quoted_res: QuotedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(quoted_res.rate, Uint128::from(150u128));
+
+    let res = query(deps.as_ref(), mock_env(), query_msg(19)).unwrap_err();
+    assert_eq!(res, StdError::generic_err("decimals must be at most 18"));
+}
+
+#[test]
+fn update_feeders() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let query_feeder = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Feeder {
+                asset_token: asset_token.to_string(),
+            },
+        )
+        .unwrap();
+        let feeder_res: FeederResponse = from_binary(&res).unwrap();
+        feeder_res.feeder
+    };
+
+    let msg = ExecuteMsg::UpdateFeeders {
+        updates: vec![
+            ("maapl".to_string(), "addr0001".to_string()),
+            ("mgogl".to_string(), "addr0002".to_string()),
+        ],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_feeders"),
+            attr("asset_token", "maapl"),
+            attr("feeder", "addr0001"),
+            attr("asset_token", "mgogl"),
+            attr("feeder", "addr0002"),
+        ]
+    );
+    assert_eq!(query_feeder(&deps, "maapl"), "addr0001");
+    assert_eq!(query_feeder(&deps, "mgogl"), "addr0002");
+
+    // an unknown asset fails the batch without touching the others
+    let msg = ExecuteMsg::UpdateFeeders {
+        updates: vec![
+            ("maapl".to_string(), "addr0003".to_string()),
+            ("mmsft".to_string(), "addr0003".to_string()),
+        ],
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetNotFound {});
+    assert_eq!(query_feeder(&deps, "maapl"), "addr0001");
+}
+
+#[test]
+fn query_execution_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(100u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, size: u128| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExecutionPrice {
+                asset_token: "maapl".to_string(),
+                size: Uint128::from(size),
+            },
+        )
+        .unwrap();
+        let execution_price_res: ExecutionPriceResponse = from_binary(&res).unwrap();
+        execution_price_res.price
+    };
+
+    // without slippage the spot price is returned for any size
+    assert_eq!(query_price(&deps, 10), Decimal::from_ratio(100u128, 1u128));
+    assert_eq!(query_price(&deps, 100), Decimal::from_ratio(100u128, 1u128));
+
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: Some(Decimal::permille(1)),
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(query_price(&deps, 10), Decimal::from_ratio(101u128, 1u128));
+    assert_eq!(query_price(&deps, 100), Decimal::from_ratio(110u128, 1u128));
+
+    // a huge size overflows gracefully instead of panicking
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExecutionPrice {
+            asset_token: "maapl".to_string(),
+            size: Uint128::MAX,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("value overflow"));
+}
+
+#[test]
+fn query_full_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: Some("uluna".to_string()),
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: Some(vec![(
+            "maapl".to_string(),
+            Decimal::from_ratio(3u128, 1u128),
+        )]),
+        source: Some("nasdaq".to_string()),
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        full_price_res,
+        FullPriceResponse {
+            asset_token: "maapl".to_string(),
+            feeder: "addr0000".to_string(),
+            decimals: None,
+            price: Decimal::from_ratio(150u128, 1u128),
+            ema: Decimal::from_ratio(150u128, 1u128),
+            prev_price: Decimal::zero(),
+            last_updated_time: mock_env().block.time.seconds(),
+            source: Some("nasdaq".to_string()),
+            alt_price: Some(Decimal::from_ratio(3u128, 1u128)),
+            alt_last_updated_time: mock_env().block.time.seconds(),
+            avg_update_interval: None,
+            frozen: false,
+            pause_reason: None,
+            was_clamped: false,
+            derivation: None,
+            action_count: 2u64,
+            update_count: 1u64,
+            set_by: Some("addr0000".to_string()),
+            authorization: Some(PriceAuthorization::Feeder),
+        }
+    );
+}
+
+#[test]
+fn freeze_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed_msg = |price: u128| ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, feed_msg(100)).unwrap();
+
+    let msg = ExecuteMsg::FreezeAsset {
+        asset_token: "maapl".to_string(),
+        pause_reason: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "freeze_asset"), attr("asset_token", "maapl")]
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, feed_msg(200)).unwrap_err();
+    assert_eq!(res, ContractError::AssetFrozen {});
+
+    // the last price is still served, flagged as frozen
+    let query_msg = QueryMsg::Price {
+        base_asset: "maapl".to_string(),
+        quote_asset: "uusd".to_string(),
+        base: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::from_ratio(100u128, 1u128));
+    assert!(price_res.frozen);
+
+    let msg = ExecuteMsg::UnfreezeAsset {
+        asset_token: "maapl".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, feed_msg(200)).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::from_ratio(200u128, 1u128));
+    assert!(!price_res.frozen);
+}
+
+#[test]
+fn query_big_movers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl", "mmsft"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // maapl moves 20% down, mgogl 1% up and mmsft is fed only once
+    let feeds = vec![
+        vec![
+            ("maapl".to_string(), Decimal::from_ratio(100u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(100u128, 1u128)),
+            ("mmsft".to_string(), Decimal::from_ratio(100u128, 1u128)),
+        ],
+        vec![
+            ("maapl".to_string(), Decimal::from_ratio(80u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(101u128, 1u128)),
+        ],
+    ];
+    for prices in feeds {
+        let msg = ExecuteMsg::FeedPrice {
+            prices,
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::BigMovers {
+            threshold: Decimal::percent(5),
+        },
+    )
+    .unwrap();
+    let big_movers_res: BigMoversResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        big_movers_res,
+        BigMoversResponse {
+            asset_tokens: vec!["maapl".to_string()],
+        }
+    );
+}
+
+#[test]
+fn query_price_never_fed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_msg = QueryMsg::Price {
+        base_asset: "maapl".to_string(),
+        quote_asset: "uusd".to_string(),
+        base: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::zero());
+    assert!(price_res.never_fed);
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert!(!price_res.never_fed);
+}
+
+#[test]
+fn import_prices() {
+    let mut deps = mock_dependencies_with_querier();
+    deps.querier.with_oracle_price(&[
+        (
+            &"maapl".to_string(),
+            &(Decimal::from_ratio(150u128, 1u128), 1000u64),
+        ),
+        (
+            &"mgogl".to_string(),
+            &(Decimal::from_ratio(2500u128, 1u128), 2000u64),
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl", "mmsft"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::ImportPrices {
+        source_oracle: "oracle0000".to_string(),
+        asset_tokens: vec![
+            "maapl".to_string(),
+            "mgogl".to_string(),
+            "mmsft".to_string(),
+        ],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "import_prices"),
+            attr("source_oracle", "oracle0000"),
+            attr("imported", "maapl"),
+            attr("imported", "mgogl"),
+            attr("skipped", "mmsft"),
+        ]
+    );
+
+    for (asset_token, price, last_updated_time) in [
+        ("maapl", Decimal::from_ratio(150u128, 1u128), 1000u64),
+        ("mgogl", Decimal::from_ratio(2500u128, 1u128), 2000u64),
+        ("mmsft", Decimal::zero(), 0u64),
+    ]
+    .iter()
+    {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: asset_token.to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(full_price_res.price, *price);
+        assert_eq!(full_price_res.last_updated_time, *last_updated_time);
+    }
+}
+
+#[test]
+fn register_asset_max_assets() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: Some(2u64),
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let register_msg = |asset_token: &str| ExecuteMsg::RegisterAsset {
+        asset_token: asset_token.to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("maapl"),
+    )
+    .unwrap();
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("mgogl"),
+    )
+    .unwrap();
+
+    // updating the feeder of a registered asset is still allowed at the cap
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("maapl"),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("mmsft"),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::TooManyAssets { max_assets: 2 });
+
+    // removing an asset frees its slot
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: false,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), info, register_msg("mmsft")).unwrap();
+}
+
+#[test]
+fn query_asset_tokens_with_prefix() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mamzn", "mgogl", "mabnb", "maaaa"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let query_prefix = |prefix: &str, limit: u32| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AssetTokensWithPrefix {
+                prefix: prefix.to_string(),
+                limit,
+            },
+        )
+        .unwrap();
+        let asset_tokens_res: AssetTokensResponse = from_binary(&res).unwrap();
+        asset_tokens_res.asset_tokens
+    };
+
+    assert_eq!(
+        query_prefix("ma", 10),
+        vec![
+            "maaaa".to_string(),
+            "maapl".to_string(),
+            "mabnb".to_string(),
+            "mamzn".to_string(),
+        ]
+    );
+    assert_eq!(
+        query_prefix("maa", 10),
+        vec!["maaaa".to_string(), "maapl".to_string()]
+    );
+    assert_eq!(
+        query_prefix("ma", 2),
+        vec!["maaaa".to_string(), "maapl".to_string()]
+    );
+    assert!(query_prefix("mt", 10).is_empty());
+}
+
+#[test]
+fn query_price_display_decimals() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![(
+            "maapl".to_string(),
+            Decimal::from_ratio(123_456_789u128, 1_000_000u128),
+        )],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_msg = QueryMsg::Price {
+        base_asset: "maapl".to_string(),
+        quote_asset: "uusd".to_string(),
+        base: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res.rate,
+        Decimal::from_ratio(123_456_789u128, 1_000_000u128)
+    );
+
+    let update_msg = |display_decimals: u32| ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: Some(display_decimals),
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), update_msg(19)).unwrap_err();
+    assert_eq!(res, ContractError::InvalidDisplayDecimals {});
+
+    let _res = execute(deps.as_mut(), mock_env(), info, update_msg(4)).unwrap();
+
+    // 123.456789 is truncated, not rounded, to 123.4567
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res.rate,
+        Decimal::from_ratio(1_234_567u128, 10_000u128)
+    );
+    assert_eq!(
+        price_res.ema_rate,
+        Decimal::from_ratio(1_234_567u128, 10_000u128)
+    );
+}
+
+#[test]
+fn suspend_feeder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let feed_msg = |asset_token: &str| ExecuteMsg::FeedPrice {
+        prices: vec![(asset_token.to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    let msg = ExecuteMsg::SuspendFeeder {
+        feeder: "addr0000".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "suspend_feeder"), attr("feeder", "addr0000")]
+    );
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let info = mock_info("addr0000", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, feed_msg(asset_token)).unwrap_err();
+        assert_eq!(res, ContractError::FeederSuspended {});
+    }
+
+    let msg = ExecuteMsg::ResumeFeeder {
+        feeder: "addr0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, feed_msg(asset_token)).unwrap();
+    }
+}
+
+#[test]
+fn query_safe_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: 1060u64,
+        },
+    )
+    .unwrap();
+    let safe_price_res: SafePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        safe_price_res,
+        SafePriceResponse {
+            price: Decimal::from_ratio(150u128, 1u128),
+            last_updated: 1000u64,
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: 1061u64,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+}
+
+#[test]
+fn feed_price_only_if_changed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed_msg = |price: u128| ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: Some(Decimal::percent(1)),
+    };
+    let query_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: "maapl".to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        (full_price_res.price, full_price_res.last_updated_time)
+    };
+
+    // the first feed always applies
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), feed_msg(1000)).unwrap();
+    assert_eq!(
+        query_price(&deps),
+        (Decimal::from_ratio(1000u128, 1u128), 1000u64)
+    );
+
+    // a 0.5% move is within tolerance
+    env.block.time = Timestamp::from_seconds(1010u64);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), feed_msg(1005)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "price_feed"), attr("skipped", "maapl")]
+    );
+    assert_eq!(
+        query_price(&deps),
+        (Decimal::from_ratio(1000u128, 1u128), 1000u64)
+    );
+
+    // a 2% move is applied
+    env.block.time = Timestamp::from_seconds(1020u64);
+    let _res = execute(deps.as_mut(), env, info, feed_msg(1020)).unwrap();
+    assert_eq!(
+        query_price(&deps),
+        (Decimal::from_ratio(1020u128, 1u128), 1020u64)
+    );
+}
+
+#[test]
+fn set_last_update_time() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+
+    let set_msg = |time: u64| ExecuteMsg::SetLastUpdateTime {
+        asset_token: "maapl".to_string(),
+        time,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, set_msg(900)).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), set_msg(1001)).unwrap_err();
+    assert_eq!(res, ContractError::FutureTime {});
+
+    let _res = execute(deps.as_mut(), env.clone(), info, set_msg(900)).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.last_updated_time, 900u64);
+}
+
+#[test]
+fn register_asset_fetch_decimals() {
+    let mut deps = mock_dependencies_with_querier();
+    deps.querier.with_token_decimals(6u8);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the supplied value is ignored when fetching from the token
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: Some(18u8),
+        fetch_decimals: true,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mgogl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: Some(8u8),
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // messages written before fetch_decimals existed keep trusting decimals
+    let msg: ExecuteMsg = from_slice(
+        br#"{"register_asset":{"asset_token":"mnflx","feeder":"addr0000","decimals":8}}"#,
+    )
+    .unwrap();
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, decimals) in [("maapl", 6u8), ("mgogl", 8u8), ("mnflx", 8u8)].iter() {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: asset_token.to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(full_price_res.decimals, Some(*decimals));
+    }
+}
+
+#[test]
+fn query_feeders() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, feeder) in [
+        ("maapl", "addr0000"),
+        ("mgogl", "addr0001"),
+        ("mmsft", "addr0000"),
+    ]
+    .iter()
+    {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some(feeder.to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let query_feeders = |start_after: Option<String>, limit: Option<u32>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Feeders { start_after, limit },
+        )
+        .unwrap();
+        let feeders_res: FeedersResponse = from_binary(&res).unwrap();
+        feeders_res.feeders
+    };
+
+    let mut feeders = query_feeders(None, None);
+    feeders.sort_by(|a, b| a.feeder.cmp(&b.feeder));
+    assert_eq!(
+        feeders,
+        vec![
+            FeedersResponseElem {
+                feeder: "addr0000".to_string(),
+                asset_count: 2,
+            },
+            FeedersResponseElem {
+                feeder: "addr0001".to_string(),
+                asset_count: 1,
+            },
+        ]
+    );
+
+    // paging continues after the last returned feeder
+    let first_page = query_feeders(None, Some(1));
+    assert_eq!(first_page.len(), 1);
+    let second_page = query_feeders(Some(first_page[0].feeder.clone()), None);
+    assert_eq!(second_page.len(), 1);
+    assert_ne!(first_page[0].feeder, second_page[0].feeder);
+}
+
+#[test]
+fn query_health_score() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, time: u64, aapl: u128, gogl: u128| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![
+                ("maapl".to_string(), Decimal::from_ratio(aapl, 1u128)),
+                ("mgogl".to_string(), Decimal::from_ratio(gogl, 1u128)),
+            ],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    };
+
+    let health_score = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str, now: u64| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::HealthScore {
+                asset_token: asset_token.to_string(),
+                now,
+            },
+        )
+        .unwrap();
+        let health_score_res: HealthScoreResponse = from_binary(&res).unwrap();
+        health_score_res.score
+    };
+
+    feed(&mut deps, 1000u64, 100u128, 100u128);
+    feed(&mut deps, 1010u64, 100u128, 120u128);
+
+    // fresh and unchanged
+    assert_eq!(health_score(&deps, "maapl", 1010u64), 100u8);
+    // half the validity period old: 100 - 25
+    assert_eq!(health_score(&deps, "maapl", 1040u64), 75u8);
+    // stale and moved 20%
+    assert_eq!(health_score(&deps, "mgogl", 1100u64), 0u8);
+
+    feed(&mut deps, 1020u64, 102u128, 120u128);
+    // moved 2%: 100 - 10
+    assert_eq!(health_score(&deps, "maapl", 1020u64), 90u8);
+}
+
+#[test]
+fn feed_price_global_feeder_allowlist() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, feeder) in [("maapl", "addr0000"), ("mgogl", "addr0001")].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some(feeder.to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: Some(vec!["addr0000".to_string()]),
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res.global_feeder_allowlist,
+        vec!["addr0000".to_string()]
+    );
+
+    let feed_msg = |asset_token: &str| ExecuteMsg::FeedPrice {
+        prices: vec![(asset_token.to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    // allowlisted feeder of its own asset
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, feed_msg("maapl")).unwrap();
+
+    // registered feeder missing from the allowlist
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), feed_msg("mgogl")).unwrap_err();
+    assert_eq!(res, ContractError::FeederNotAllowlisted {});
+
+    // an empty allowlist is permissive again
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: Some(vec![]),
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), info, feed_msg("mgogl")).unwrap();
+}
+
+#[test]
+fn register_and_feed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAndFeed {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        price: Decimal::from_ratio(150u128, 1u128),
+    };
+
+    // only the owner can register
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    // the feed must carry a non-zero price
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::RegisterAndFeed {
+            asset_token: "maapl".to_string(),
+            feeder: Some("addr0000".to_string()),
+            price: Decimal::zero(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::ZeroPrice {});
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    // an existing asset can not be registered again
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetAlreadyRegistered {});
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::AssetExists {
+            asset_token: "maapl".to_string(),
+        },
+    )
+    .unwrap();
+    let asset_exists_res: AssetExistsResponse = from_binary(&res).unwrap();
+    assert!(asset_exists_res.exists);
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: 1000u64,
+        },
+    )
+    .unwrap();
+    let safe_price_res: SafePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        safe_price_res,
+        SafePriceResponse {
+            price: Decimal::from_ratio(150u128, 1u128),
+            last_updated: 1000u64,
+        }
+    );
+}
+
+#[test]
+fn query_price_root() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let price_root = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PriceRoot {}).unwrap();
+        let price_root_res: PriceRootResponse = from_binary(&res).unwrap();
+        price_root_res.root
+    };
+
+    assert_eq!(price_root(&deps), Binary::from(vec![0u8; 32]));
+
+    for asset_token in ["mgogl", "maapl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(2500u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let aapl_leaf = Sha256::digest(b"maapl,150,1000").to_vec();
+    let gogl_leaf = Sha256::digest(b"mgogl,2500,1000").to_vec();
+    let expected = Sha256::digest(&[aapl_leaf, gogl_leaf].concat());
+    let root = price_root(&deps);
+    assert_eq!(root, Binary::from(expected.to_vec()));
+
+    // stable while prices do not change
+    assert_eq!(price_root(&deps), root);
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mgogl".to_string(), Decimal::from_ratio(2501u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_ne!(price_root(&deps), root);
+}
+
+#[test]
+fn query_full_price_avg_update_interval() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let avg_update_interval = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: "maapl".to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        full_price_res.avg_update_interval
+    };
+
+    assert_eq!(avg_update_interval(&deps), None);
+
+    for (i, time) in [1000u64, 1030u64, 1060u64, 1090u64].iter().enumerate() {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(*time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(
+                "maapl".to_string(),
+                Decimal::from_ratio(150u128 + i as u128, 1u128),
+            )],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        if i == 0 {
+            assert_eq!(avg_update_interval(&deps), None);
+        }
+    }
+
+    assert_eq!(avg_update_interval(&deps), Some(30u64));
+}
+
+#[test]
+fn query_price_if_fresh() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let price_if_fresh = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, now: u64, max_age: u64| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PriceIfFresh {
+                asset_token: "maapl".to_string(),
+                now,
+                max_age,
+            },
+        )
+    };
+
+    // never fed
+    let res = price_if_fresh(&deps, 1000u64, 3600u64).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // tolerance is independent of the 60s validity period
+    let res = price_if_fresh(&deps, 1300u64, 300u64).unwrap();
+    let price_res: SafePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        SafePriceResponse {
+            price: Decimal::from_ratio(150u128, 1u128),
+            last_updated: 1000u64,
+        }
+    );
+
+    let res = price_if_fresh(&deps, 1011u64, 10u64).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+}
+
+#[test]
+fn feed_price_relay_contract() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (asset_token, feeder) in [("maapl", "addr0000"), ("mgogl", "addr0001")].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some(feeder.to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: Some("relay0000".to_string()),
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.relay_contract, Some("relay0000".to_string()));
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(2500u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    // neither the relay nor the feeder of both assets
+    let info = mock_info("addr0002", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("relay0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            base_asset: "mgogl".to_string(),
+            quote_asset: "uusd".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::from_ratio(2500u128, 1u128));
+}
+
+#[test]
+fn feed_price_notifies_subscribers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_subscribers = |count: usize| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: Some((0..count).map(|i| format!("sub{:04}", i)).collect()),
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        update_subscribers(11),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::TooManySubscribers {
+            max_subscribers: 10
+        }
+    );
+    let _res = execute(deps.as_mut(), mock_env(), info, update_subscribers(2)).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let hook_msg = to_binary(&PriceHookMsg::PriceUpdate {
+        asset_token: "maapl".to_string(),
+        price: Decimal::from_ratio(150u128, 1u128),
+        last_updated_time: 1000u64,
+    })
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "sub0000".to_string(),
+                    funds: vec![],
+                    msg: hook_msg.clone(),
+                }),
+                PRICE_HOOK_REPLY_ID,
+            )
+            .with_gas_limit(PRICE_HOOK_GAS_LIMIT),
+            SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "sub0001".to_string(),
+                    funds: vec![],
+                    msg: hook_msg,
+                }),
+                PRICE_HOOK_REPLY_ID,
+            )
+            .with_gas_limit(PRICE_HOOK_GAS_LIMIT),
+        ]
+    );
+}
+
+#[test]
+fn query_path_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl", "mmsft"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(3000u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let path_price = |env: cosmwasm_std::Env, path: &[&str]| {
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PathPrice {
+                path: path.iter().map(|asset| asset.to_string()).collect(),
+                explain: None,
+            },
+        )
+    };
+
+    // maapl -> mgogl -> uusd: 150 / 3000 * 3000
+    let res = path_price(env.clone(), &["maapl", "mgogl", "uusd"]).unwrap();
+    let path_price_res: PathPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        path_price_res,
+        PathPriceResponse {
+            rate: Decimal::from_ratio(150u128, 1u128),
+            last_updated: 1000u64,
+            derivation: None,
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PathPrice {
+            path: vec!["maapl".to_string(), "mgogl".to_string(), "uusd".to_string()],
+            explain: Some(true),
+        },
+    )
+    .unwrap();
+    let path_price_res: PathPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        path_price_res.derivation,
+        Some(vec![
+            "maapl/mgogl = 0.05".to_string(),
+            "mgogl/uusd = 3000".to_string(),
+        ])
+    );
+
+    // uusd -> mgogl -> maapl: 1 / 3000 * 3000 / 150, rounded by decimal_division
+    let res = path_price(env.clone(), &["uusd", "mgogl", "maapl"]).unwrap();
+    let path_price_res: PathPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        path_price_res.rate,
+        Decimal::from_ratio(666666u128, 100_000_000u128)
+    );
+
+    // mmsft was never fed
+    let res = path_price(env.clone(), &["maapl", "mmsft", "uusd"]).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old for mmsft"));
+
+    let res = path_price(env.clone(), &["maapl", "mtsla", "uusd"]).unwrap_err();
+    assert_eq!(res, StdError::generic_err("No price for mtsla"));
+
+    env.block.time = Timestamp::from_seconds(1061u64);
+    let res = path_price(env, &["maapl", "mgogl", "uusd"]).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old for maapl"));
+}
+
+#[test]
+fn update_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the new address is already mapped to another asset
+    let msg = ExecuteMsg::UpdateToken {
+        asset_token: "maapl".to_string(),
+        new_asset_token: "mgogl".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetAlreadyRegistered {});
+
+    let msg = ExecuteMsg::UpdateToken {
+        asset_token: "maapl".to_string(),
+        new_asset_token: "maapl2".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Feeder {
+            asset_token: "maapl2".to_string(),
+        },
+    )
+    .unwrap();
+    let feeder_res: FeederResponse = from_binary(&res).unwrap();
+    assert_eq!(feeder_res.feeder, "addr0000");
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "maapl2".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.price, Decimal::from_ratio(150u128, 1u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssetExists {
+            asset_token: "maapl".to_string(),
+        },
+    )
+    .unwrap();
+    let asset_exists_res: AssetExistsResponse = from_binary(&res).unwrap();
+    assert!(!asset_exists_res.exists);
+}
+
+#[test]
+fn register_asset_invalid_address() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let register_msg = |asset_token: &str, feeder: &str| ExecuteMsg::RegisterAsset {
+        asset_token: asset_token.to_string(),
+        feeder: Some(feeder.to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("m", "addr0000"),
+    )
+    .unwrap_err();
+    assert_eq!(res.to_string(), "invalid token address");
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        register_msg("maapl", "a"),
+    )
+    .unwrap_err();
+    assert_eq!(res.to_string(), "invalid feeder address");
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        register_msg("maapl", "addr0000"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn query_market_index() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl", "mmsft", "mtsla"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let feed =
+        |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, time: u64, prices: Vec<(&str, u128)>| {
+            let mut env = mock_env();
+            env.block.time = Timestamp::from_seconds(time);
+            let msg = ExecuteMsg::FeedPrice {
+                prices: prices
+                    .into_iter()
+                    .map(|(asset_token, price)| {
+                        (asset_token.to_string(), Decimal::from_ratio(price, 1u128))
+                    })
+                    .collect(),
+                alt_prices: None,
+                source: None,
+                idempotency_key: None,
+                only_if_changed: None,
+            };
+            let info = mock_info("addr0000", &[]);
+            let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        };
+
+    // mmsft goes stale, mtsla is never fed
+    feed(&mut deps, 1000u64, vec![("mmsft", 1000u128)]);
+    feed(
+        &mut deps,
+        1100u64,
+        vec![("maapl", 100u128), ("mgogl", 200u128)],
+    );
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1100u64);
+    let res = query(deps.as_ref(), env, QueryMsg::MarketIndex {}).unwrap();
+    let market_index_res: MarketIndexResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        market_index_res,
+        MarketIndexResponse {
+            average_price: Decimal::from_ratio(150u128, 1u128),
+            asset_count: 2,
+        }
+    );
+}
+
+#[test]
+fn feed_price_feed_fee() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: Some(coin(100u128, "uusd")),
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    // fee without a collector
+    let info = mock_info("addr0000", &[coin(100u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::FeeCollectorNotSet {});
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: Some("collector0000".to_string()),
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, update_msg).unwrap();
+
+    let info = mock_info("addr0000", &[coin(99u128, "uusd"), coin(100u128, "uluna")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientFeedFee {
+            fee: coin(100u128, "uusd")
+        }
+    );
+
+    let info = mock_info("addr0000", &[coin(100u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "collector0000".to_string(),
+            amount: vec![coin(100u128, "uusd")],
+        }))]
+    );
+}
+
+#[test]
+fn query_update_time_diff() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    for (asset_token, time) in [("maapl", 1000u64), ("mgogl", 1045u64)].iter() {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(*time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(asset_token.to_string(), Decimal::from_ratio(150u128, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    let update_time_diff = |a: &str, b: &str| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UpdateTimeDiff {
+                a: a.to_string(),
+                b: b.to_string(),
+            },
+        )
+    };
+
+    let res = update_time_diff("maapl", "mgogl").unwrap();
+    let diff_res: UpdateTimeDiffResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        diff_res,
+        UpdateTimeDiffResponse {
+            diff: 45u64,
+            fresher: Some("mgogl".to_string()),
+        }
+    );
+
+    let res = update_time_diff("maapl", "maapl").unwrap();
+    let diff_res: UpdateTimeDiffResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        diff_res,
+        UpdateTimeDiffResponse {
+            diff: 0u64,
+            fresher: None,
+        }
+    );
+
+    let res = update_time_diff("maapl", "mmsft").unwrap_err();
+    assert_eq!(res, StdError::generic_err("mmsft is not registered"));
+}
+
+#[test]
+fn query_price_on_stale() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let query_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, env: cosmwasm_std::Env| {
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+    };
+
+    let res = query_price(&deps, env.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert!(!price_res.stale);
+
+    // flagged by default
+    env.block.time = Timestamp::from_seconds(1061u64);
+    let res = query_price(&deps, env.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert!(price_res.stale);
+    assert_eq!(price_res.rate, Decimal::from_ratio(150u128, 1u128));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: Some(StaleResponse::Error),
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query_price(&deps, env).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+}
+
+#[test]
+fn query_prices_map() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for asset_token in ["maapl", "mgogl"].iter() {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let info = mock_info("owner0000", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(2500u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PricesMap {
+            asset_tokens: vec![
+                "mgogl".to_string(),
+                "mmsft".to_string(),
+                "maapl".to_string(),
+            ],
+        },
+    )
+    .unwrap();
+    let prices_map_res: PricesMapResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        prices_map_res,
+        PricesMapResponse {
+            prices: vec![
+                ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+                ("mgogl".to_string(), Decimal::from_ratio(2500u128, 1u128)),
+            ],
+        }
+    );
+}
+
+#[test]
+fn feed_price_maintenance_window() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: Some((1000u64, 2000u64)),
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, time: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), env, info, msg)
+    };
+
+    let _res = feed(&mut deps, 999u64).unwrap();
+
+    let res = feed(&mut deps, 1000u64).unwrap_err();
+    assert_eq!(res, ContractError::MaintenanceWindow {});
+
+    // queries keep working during maintenance
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "uusd".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::from_ratio(150u128, 1u128));
+
+    let _res = feed(&mut deps, 2000u64).unwrap();
+}
+
+#[test]
+fn query_raw_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RawConfig {}).unwrap();
+    let raw_config_res: RawConfigResponse = from_binary(&res).unwrap();
+
+    let owner_raw = deps.api.addr_canonicalize("owner0000").unwrap();
+    let owner_hex: String = owner_raw
+        .as_slice()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    assert_eq!(
+        raw_config_res,
+        RawConfigResponse {
+            owner: owner_hex,
+            base_asset: "uusd".to_string(),
+        }
+    );
+}
+
+#[test]
+fn freeze_asset_pause_reason() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let full_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: "maapl".to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        (full_price_res.frozen, full_price_res.pause_reason)
+    };
+
+    let msg = ExecuteMsg::FreezeAsset {
+        asset_token: "maapl".to_string(),
+        pause_reason: Some("x".repeat(257)),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::PauseReasonTooLong { max_length: 256 });
+
+    let msg = ExecuteMsg::FreezeAsset {
+        asset_token: "maapl".to_string(),
+        pause_reason: Some("stock split".to_string()),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(full_price(&deps), (true, Some("stock split".to_string())));
+
+    let msg = ExecuteMsg::UnfreezeAsset {
+        asset_token: "maapl".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(full_price(&deps), (false, None));
+}
+
+#[test]
+fn feed_price_out_of_band() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_band = |out_of_band: OutOfBand| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: Some((
+            Decimal::from_ratio(100u128, 1u128),
+            Decimal::from_ratio(200u128, 1u128),
+        )),
+        out_of_band: Some(out_of_band),
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u128| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+    let full_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: "maapl".to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        (full_price_res.price, full_price_res.was_clamped)
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        update_band(OutOfBand::Reject),
+    )
+    .unwrap();
+    let res = feed(&mut deps, 250u128).unwrap_err();
+    assert_eq!(res, ContractError::PriceOutOfBand {});
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_band(OutOfBand::Clamp),
+    )
+    .unwrap();
+
+    let _res = feed(&mut deps, 250u128).unwrap();
+    assert_eq!(
+        full_price(&deps),
+        (Decimal::from_ratio(200u128, 1u128), true)
+    );
+
+    let _res = feed(&mut deps, 50u128).unwrap();
+    assert_eq!(
+        full_price(&deps),
+        (Decimal::from_ratio(100u128, 1u128), true)
+    );
+
+    let _res = feed(&mut deps, 150u128).unwrap();
+    assert_eq!(
+        full_price(&deps),
+        (Decimal::from_ratio(150u128, 1u128), false)
+    );
+}
+
+#[test]
+fn schedule_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env_at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    };
+    let owner = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        config.owner
+    };
+
+    let msg = ExecuteMsg::ScheduleConfig {
+        changes: ConfigChanges {
+            owner: Some("owner0001".to_string()),
+            ..ConfigChanges::default()
+        },
+        effective_at: 2000u64,
+    };
+
+    // only the owner can schedule
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env_at(1000), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env_at(1000), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "schedule_config"),
+            attr("effective_at", "2000"),
+        ]
+    );
+
+    // not effective yet
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(1999),
+        info,
+        ExecuteMsg::ApplyScheduledConfig {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NoScheduledConfig {});
+    assert_eq!("owner0000", owner(&deps));
+
+    // anyone can apply once effective
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(2000),
+        info,
+        ExecuteMsg::ApplyScheduledConfig {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "apply_scheduled_config")]
+    );
+    assert_eq!("owner0001", owner(&deps));
+
+    // applied changes are cleared
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(2001),
+        info,
+        ExecuteMsg::ApplyScheduledConfig {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NoScheduledConfig {});
+
+    // scheduled changes are also applied by any other message
+    let info = mock_info("owner0001", &[]);
+    let msg = ExecuteMsg::ScheduleConfig {
+        changes: ConfigChanges {
+            owner: Some("owner0002".to_string()),
+            ..ConfigChanges::default()
+        },
+        effective_at: 3000u64,
+    };
+    execute(deps.as_mut(), env_at(2500), info, msg).unwrap();
+
+    let info = mock_info("owner0001", &[]);
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mapl0000".to_string(),
+        feeder: Some("feeder0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    execute(deps.as_mut(), env_at(2999), info, msg).unwrap();
+    assert_eq!("owner0001", owner(&deps));
+
+    // the new owner takes effect before the message is handled
+    let info = mock_info("owner0001", &[]);
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mgogl0000".to_string(),
+        feeder: Some("feeder0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let res = execute(deps.as_mut(), env_at(3000), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+    assert_eq!("owner0002", owner(&deps));
+}
+
+#[test]
+fn schedule_config_conflict() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env_at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    };
+    let update_max_multiplier = |max_multiplier: Decimal| ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: Some(max_multiplier),
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::ScheduleConfig {
+        changes: ConfigChanges {
+            min_multiplier: Some(Decimal::percent(200)),
+            ..ConfigChanges::default()
+        },
+        effective_at: 2000u64,
+    };
+    let _res = execute(deps.as_mut(), env_at(1000), info.clone(), msg).unwrap();
+
+    // updates the scheduled changes could not be applied on top of are rejected
+    let msg = update_max_multiplier(Decimal::percent(100));
+    let res = execute(deps.as_mut(), env_at(1000), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidMultiplierBounds {});
+    let msg = update_max_multiplier(Decimal::percent(300));
+    let _res = execute(deps.as_mut(), env_at(1000), info.clone(), msg).unwrap();
+
+    // stored changes that no longer apply are dropped instead of failing every message
+    store_scheduled_config(
+        deps.as_mut().storage,
+        &ScheduledConfig {
+            changes: ConfigChanges {
+                min_multiplier: Some(Decimal::percent(400)),
+                ..ConfigChanges::default()
+            },
+            effective_at: 2000u64,
+        },
+    )
+    .unwrap();
+    let msg = ExecuteMsg::ScheduleConfig {
+        changes: ConfigChanges {
+            owner: Some("owner0001".to_string()),
+            ..ConfigChanges::default()
+        },
+        effective_at: 3000u64,
+    };
+    let res = execute(deps.as_mut(), env_at(2000), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "schedule_config"),
+            attr("effective_at", "3000"),
+            attr("scheduled_config", "dropped"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.min_multiplier, None);
+    assert_eq!(config.max_multiplier, Some(Decimal::percent(300)));
+
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(
+        deps.as_mut(),
+        env_at(3000),
+        info,
+        ExecuteMsg::ApplyScheduledConfig {},
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.owner, "owner0001");
+}
+
+#[test]
+fn pause_status() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let pause_status = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap();
+        let pause_status_res: PauseStatusResponse = from_binary(&res).unwrap();
+        pause_status_res
+    };
+    let feed_msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+
+    assert_eq!(
+        pause_status(&deps),
+        PauseStatusResponse {
+            paused: false,
+            reason: None,
+            since: 0u64,
+        }
+    );
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let msg = ExecuteMsg::SetPaused {
+        paused: true,
+        reason: Some("feeder key rotation".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        pause_status(&deps),
+        PauseStatusResponse {
+            paused: true,
+            reason: Some("feeder key rotation".to_string()),
+            since: 1000u64,
+        }
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, feed_msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Paused {});
+
+    env.block.time = Timestamp::from_seconds(2000);
+    let msg = ExecuteMsg::SetPaused {
+        paused: false,
+        reason: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        pause_status(&deps),
+        PauseStatusResponse {
+            paused: false,
+            reason: None,
+            since: 2000u64,
+        }
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, feed_msg).unwrap();
+}
+
+#[test]
+fn post_unpause_grace() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: Some(30u64),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let set_paused = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, paused: bool, time: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::SetPaused {
+            paused,
+            reason: None,
+        };
+        execute(deps.as_mut(), env, mock_info("owner0000", &[]), msg).unwrap();
+    };
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, time: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::one())],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+    };
+    let warming_up = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, time: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(time);
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        price_res.warming_up
+    };
+
+    feed(&mut deps, 1000);
+    assert!(!warming_up(&deps, 1000));
+
+    set_paused(&mut deps, true, 1000);
+    assert!(!warming_up(&deps, 1010));
+
+    // feeds are accepted during the grace window, prices are flagged until it elapses
+    set_paused(&mut deps, false, 1020);
+    feed(&mut deps, 1020);
+    assert!(warming_up(&deps, 1020));
+    assert!(warming_up(&deps, 1049));
+    assert!(!warming_up(&deps, 1050));
+}
+
+#[test]
+fn feed_basket() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl", "mindex"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let feed_basket = |components: Vec<(&str, Decimal)>| ExecuteMsg::FeedBasket {
+        index_token: "mindex".to_string(),
+        components: components
+            .into_iter()
+            .map(|(component, price)| (component.to_string(), price))
+            .collect(),
+    };
+    let price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: asset_token.to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        price_res.rate
+    };
+
+    let feeder_info = mock_info("addr0000", &[]);
+    let msg = feed_basket(vec![("maapl", Decimal::percent(100))]);
+    let res = execute(deps.as_mut(), mock_env(), feeder_info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::NotAnIndex {});
+
+    // index can not contain itself
+    let asset_config_msg = |basket: Vec<(&str, Decimal)>| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "mindex".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: Some(
+            basket
+                .into_iter()
+                .map(|(component, units)| (component.to_string(), units))
+                .collect(),
+        ),
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let msg = asset_config_msg(vec![("mindex", Decimal::one())]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidBasket {});
+
+    // index = 2 maapl + 0.5 mgogl
+    let msg = asset_config_msg(vec![
+        ("maapl", Decimal::percent(200)),
+        ("mgogl", Decimal::percent(50)),
+    ]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // partial updates are rejected
+    let msg = feed_basket(vec![("maapl", Decimal::percent(100))]);
+    let res = execute(deps.as_mut(), mock_env(), feeder_info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::BasketMismatch {});
+
+    let info = mock_info("addr0001", &[]);
+    let msg = feed_basket(vec![
+        ("maapl", Decimal::percent(100)),
+        ("mgogl", Decimal::percent(1000)),
+    ]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let msg = feed_basket(vec![
+        ("maapl", Decimal::percent(100)),
+        ("mgogl", Decimal::percent(1000)),
+    ]);
+    let res = execute(deps.as_mut(), mock_env(), feeder_info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "feed_basket"),
+            attr("index_token", "mindex"),
+            attr("asset", "maapl"),
+            attr("price", "1"),
+            attr("asset", "mgogl"),
+            attr("price", "10"),
+            attr("index_price", "7"),
+        ]
+    );
+    assert_eq!(price(&deps, "maapl"), Decimal::percent(100));
+    assert_eq!(price(&deps, "mgogl"), Decimal::percent(1000));
+    assert_eq!(price(&deps, "mindex"), Decimal::percent(700));
+
+    // component order does not matter
+    let msg = feed_basket(vec![
+        ("mgogl", Decimal::percent(800)),
+        ("maapl", Decimal::percent(150)),
+    ]);
+    let _res = execute(deps.as_mut(), mock_env(), feeder_info, msg).unwrap();
+    assert_eq!(price(&deps, "mindex"), Decimal::percent(700));
+    assert_eq!(price(&deps, "maapl"), Decimal::percent(150));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "mindex".to_string(),
+            explain: Some(true),
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    let mut derivation = full_price_res.derivation.unwrap();
+    derivation.sort();
+    assert_eq!(
+        derivation,
+        vec![
+            "maapl: 2 * 1.5 = 3".to_string(),
+            "mgogl: 0.5 * 8 = 4".to_string(),
+        ]
+    );
+
+    // components can not be removed while the index needs them
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: true,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetInBasket {});
+
+    // a moved component stays in the basket under its new token
+    let msg = ExecuteMsg::UpdateToken {
+        asset_token: "mgogl".to_string(),
+        new_asset_token: "mgogl0001".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = feed_basket(vec![
+        ("maapl", Decimal::percent(100)),
+        ("mgogl", Decimal::percent(1000)),
+    ]);
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::BasketMismatch {});
+
+    let msg = feed_basket(vec![
+        ("maapl", Decimal::percent(100)),
+        ("mgogl0001", Decimal::percent(1000)),
+    ]);
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(price(&deps, "mindex"), Decimal::percent(700));
+}
+
+#[test]
+fn feed_price_limits() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let update_config = |price_limits: (Decimal, Decimal)| ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: Some(price_limits),
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: Decimal| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), price)],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let msg = update_config((Decimal::percent(200), Decimal::percent(100)));
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidPriceLimits {});
+
+    let min_price = Decimal::from_ratio(1u128, 1_000_000u128);
+    let max_price = Decimal::from_ratio(1_000_000u128, 1u128);
+    let msg = update_config((min_price, max_price));
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.price_limits, Some((min_price, max_price)));
+
+    // limits are inclusive
+    feed(&mut deps, min_price).unwrap();
+    feed(&mut deps, max_price).unwrap();
+
+    let res = feed(
+        &mut deps,
+        Decimal::from_ratio(999_999u128, 1_000_000_000_000u128),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::PriceBelowMin { min_price });
+    assert_eq!(res.to_string(), "price is below the minimum of 0.000001");
+
+    let res = feed(
+        &mut deps,
+        Decimal::from_ratio(1_000_000_000_001u128, 1_000_000u128),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::PriceAboveMax { max_price });
+    assert_eq!(res.to_string(), "price is above the maximum of 1000000");
+
+    // registering feeds are held to the same limits
+    let msg = ExecuteMsg::RegisterAndFeed {
+        asset_token: "mgogl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        price: Decimal::from_ratio(2_000_000u128, 1u128),
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::PriceAboveMax { max_price });
+
+    // a zero max removes the limits
+    let msg = update_config((Decimal::zero(), Decimal::zero()));
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.price_limits, None);
+
+    feed(&mut deps, Decimal::from_ratio(2_000_000u128, 1u128)).unwrap();
+}
+
+#[test]
+fn feeder_rate_limit() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    // at most 2 feeds per 100 seconds
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: Some((2u32, 100u64)),
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str, seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(asset_token.to_string(), Decimal::one())],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), env, info, msg)
+    };
+
+    // the cap is shared across the feeder's assets
+    feed(&mut deps, "maapl", 1000).unwrap();
+    feed(&mut deps, "mgogl", 1050).unwrap();
+    let res = feed(&mut deps, "maapl", 1099).unwrap_err();
+    assert_eq!(res, ContractError::FeedRateLimited {});
+
+    // the first feed leaves the window
+    feed(&mut deps, "maapl", 1100).unwrap();
+    let res = feed(&mut deps, "mgogl", 1149).unwrap_err();
+    assert_eq!(res, ContractError::FeedRateLimited {});
+    feed(&mut deps, "mgogl", 1150).unwrap();
+
+    // an unbounded window keeps every feed in it
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: Some((2u32, u64::MAX)),
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = feed(&mut deps, "maapl", 1_000_000).unwrap_err();
+    assert_eq!(res, ContractError::FeedRateLimited {});
+}
+
+#[test]
+fn all_effective_prices() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl", "mnflx"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: Some(2u32),
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>,
+                prices: Vec<(&str, Decimal)>,
+                seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: prices
+                .into_iter()
+                .map(|(asset_token, price)| (asset_token.to_string(), price))
+                .collect(),
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+    };
+    feed(
+        &mut deps,
+        vec![("maapl", Decimal::from_ratio(12345u128, 1000u128))],
+        1000,
+    );
+    feed(
+        &mut deps,
+        vec![
+            ("mgogl", Decimal::percent(200)),
+            ("mnflx", Decimal::percent(300)),
+        ],
+        1100,
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllEffectivePrices {
+            start_after: None,
+            limit: None,
+            now: 1100u64,
+        },
+    )
+    .unwrap();
+    let mut all_effective_prices_res: AllEffectivePricesResponse = from_binary(&res).unwrap();
+    all_effective_prices_res
+        .prices
+        .sort_by(|a, b| a.asset_token.cmp(&b.asset_token));
+    assert_eq!(
+        all_effective_prices_res.prices,
+        vec![
+            EffectivePricesResponseElem {
+                asset_token: "maapl".to_string(),
+                effective_price: Decimal::from_ratio(1234u128, 100u128),
+                is_stale: true,
+            },
+            EffectivePricesResponseElem {
+                asset_token: "mgogl".to_string(),
+                effective_price: Decimal::percent(200),
+                is_stale: false,
+            },
+            EffectivePricesResponseElem {
+                asset_token: "mnflx".to_string(),
+                effective_price: Decimal::percent(300),
+                is_stale: false,
+            },
+        ]
+    );
+
+    // paginates like the Prices query
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllEffectivePrices {
+            start_after: None,
+            limit: Some(2u32),
+            now: 1100u64,
+        },
+    )
+    .unwrap();
+    let first_page: AllEffectivePricesResponse = from_binary(&res).unwrap();
+    assert_eq!(first_page.prices.len(), 2);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllEffectivePrices {
+            start_after: Some(first_page.prices[1].asset_token.clone()),
+            limit: Some(2u32),
+            now: 1100u64,
+        },
+    )
+    .unwrap();
+    let second_page: AllEffectivePricesResponse = from_binary(&res).unwrap();
+    assert_eq!(second_page.prices.len(), 1);
+    assert!(!first_page.prices.contains(&second_page.prices[0]));
+}
+
+#[test]
+fn price_multiplier_tiers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let asset_config_msg =
+        |multiplier_tiers: Vec<(Decimal, Decimal)>| ExecuteMsg::UpdateAssetConfig {
+            asset_token: "maapl".to_string(),
+            ema_alpha: None,
+            slippage_per_unit: None,
+            subscribers: None,
+            price_band: None,
+            out_of_band: None,
+            basket: None,
+            price_multiplier: Some(Decimal::percent(200)),
+            multiplier_tiers: Some(multiplier_tiers),
+            max_deviation: None,
+            monotone_increasing: None,
+            peg: None,
+            peg_tolerance: None,
+            stale_message: None,
+            subscription: None,
+        };
+    let rate = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u64| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u64))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        price_res.rate
+    };
+
+    let msg = asset_config_msg(vec![
+        (Decimal::percent(1000), Decimal::percent(300)),
+        (Decimal::percent(1000), Decimal::percent(400)),
+    ]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidMultiplier {});
+
+    // tiers may be given in any order
+    let msg = asset_config_msg(vec![
+        (Decimal::percent(10000), Decimal::percent(400)),
+        (Decimal::percent(1000), Decimal::percent(300)),
+    ]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // below every tier the scalar multiplier applies
+    assert_eq!(rate(&mut deps, 5), Decimal::percent(1000));
+    assert_eq!(rate(&mut deps, 10), Decimal::percent(3000));
+    assert_eq!(rate(&mut deps, 50), Decimal::percent(15000));
+    assert_eq!(rate(&mut deps, 100), Decimal::percent(40000));
+
+    // the stored price is left unscaled
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.price, Decimal::percent(10000));
+
+    // every other priced query reports the multiplied price
+    let effective = Decimal::percent(40000);
+    let query_msgs = vec![
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: mock_env().block.time.seconds(),
+        },
+        QueryMsg::PriceIfFresh {
+            asset_token: "maapl".to_string(),
+            now: mock_env().block.time.seconds(),
+            max_age: 60u64,
+        },
+        QueryMsg::ConfirmedPrice {
+            asset_token: "maapl".to_string(),
+            min_updates: 1u64,
+        },
+    ];
+    for msg in query_msgs {
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let safe_price_res: SafePriceResponse = from_binary(&res).unwrap();
+        assert_eq!(safe_price_res.price, effective);
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PathPrice {
+            path: vec!["maapl".to_string(), "uusd".to_string()],
+            explain: None,
+        },
+    )
+    .unwrap();
+    let path_price_res: PathPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(path_price_res.rate, effective);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::MarketIndex {}).unwrap();
+    let market_index_res: MarketIndexResponse = from_binary(&res).unwrap();
+    assert_eq!(market_index_res.average_price, effective);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PricesMap {
+            asset_tokens: vec!["maapl".to_string()],
+        },
+    )
+    .unwrap();
+    let prices_map_res: PricesMapResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        prices_map_res.prices,
+        vec![("maapl".to_string(), effective)]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExecutionPrice {
+            asset_token: "maapl".to_string(),
+            size: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let execution_price_res: ExecutionPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(execution_price_res.price, effective);
+}
+
+#[test]
+fn stale_at() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let stale_at = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::StaleAt {
+                asset_token: "maapl".to_string(),
+            },
+        )
+        .unwrap();
+        let stale_at_res: StaleAtResponse = from_binary(&res).unwrap();
+        stale_at_res.stale_at
+    };
+
+    assert_eq!(stale_at(&deps), 0u64);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    assert_eq!(stale_at(&deps), 1060u64);
+
+    // the price is still fresh at stale_at
+    let safe_price = |now: u64| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SafePrice {
+                asset_token: "maapl".to_string(),
+                now,
+            },
+        )
+    };
+    assert!(safe_price(1060u64).is_ok());
+    assert!(safe_price(1061u64).is_err());
+}
+
+#[test]
+fn feed_price_duplicate_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::percent(100)),
+            ("mgogl".to_string(), Decimal::percent(200)),
+            ("maapl".to_string(), Decimal::percent(300)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::DuplicateAsset {
+            asset_token: "maapl".to_string()
+        }
+    );
+
+    // nothing was fed
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "mgogl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.last_updated_time, 0u64);
+
+    // without the repeat the batch is accepted
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::percent(100))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn feed_price_reference_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: Some(Decimal::percent(10)),
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u64| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u64))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+
+    // without a reference, feeds are checked against the current price
+    feed(&mut deps, 100).unwrap();
+    feed(&mut deps, 105).unwrap();
+    let res = feed(&mut deps, 120).unwrap_err();
+    assert_eq!(res, ContractError::PriceDeviationTooHigh {});
+
+    let msg = ExecuteMsg::SetReferencePrice {
+        asset_token: "maapl".to_string(),
+        price: Some(Decimal::from_ratio(200u128, 1u128)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_reference_price"),
+            attr("asset_token", "maapl"),
+            attr("reference_price", "200"),
+        ]
+    );
+
+    // the pinned reference replaces the current price
+    feed(&mut deps, 210).unwrap();
+    let res = feed(&mut deps, 225).unwrap_err();
+    assert_eq!(res, ContractError::PriceDeviationTooHigh {});
+    feed(&mut deps, 190).unwrap();
+
+    let msg = ExecuteMsg::SetReferencePrice {
+        asset_token: "maapl".to_string(),
+        price: Some(Decimal::zero()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidReferencePrice {});
+
+    let msg = ExecuteMsg::SetReferencePrice {
+        asset_token: "maapl".to_string(),
+        price: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    feed(&mut deps, 205).unwrap();
+}
+
+#[test]
+fn query_price_in_denom() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "uluna"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("uluna".to_string(), Decimal::from_ratio(50u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let price_in_denom = |env: cosmwasm_std::Env| {
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PriceInDenom {
+                asset_token: "maapl".to_string(),
+                via: "uluna".to_string(),
+            },
+        )
+    };
+
+    // 150 uusd / 50 uusd per uluna
+    let res = price_in_denom(env.clone()).unwrap();
+    let price_in_denom_res: PathPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_in_denom_res.rate, Decimal::from_ratio(3u128, 1u128));
+    assert_eq!(price_in_denom_res.last_updated, 1000u64);
+
+    env.block.time = Timestamp::from_seconds(1061);
+    let res = price_in_denom(env).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old for maapl"));
+}
+
+#[test]
+fn set_multipliers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(3000u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let prices = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str| {
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::FullPrice {
+                asset_token: asset_token.to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Price {
+                base_asset: asset_token.to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        (
+            full_price_res.price,
+            full_price_res.last_updated_time,
+            price_res.rate,
+        )
+    };
+
+    // the batch fails as a whole on an unknown asset
+    let msg = ExecuteMsg::SetMultipliers {
+        updates: vec![
+            ("maapl".to_string(), Decimal::percent(200)),
+            ("mnflx".to_string(), Decimal::percent(200)),
+        ],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetNotFound {});
+
+    let msg = ExecuteMsg::SetMultipliers {
+        updates: vec![
+            ("maapl".to_string(), Decimal::percent(200)),
+            ("mgogl".to_string(), Decimal::percent(50)),
+        ],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // stored prices and update times are untouched, only quotes are scaled
+    assert_eq!(
+        prices(&deps, "maapl"),
+        (
+            Decimal::from_ratio(150u128, 1u128),
+            1000u64,
+            Decimal::from_ratio(300u128, 1u128)
+        )
+    );
+    assert_eq!(
+        prices(&deps, "mgogl"),
+        (
+            Decimal::from_ratio(3000u128, 1u128),
+            1000u64,
+            Decimal::from_ratio(1500u128, 1u128)
+        )
+    );
+}
+
+#[test]
+fn multiplier_bounds() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let update_config =
+        |min_multiplier: Decimal, max_multiplier: Decimal| ExecuteMsg::UpdateConfig {
+            owner: None,
+            alt_base_asset: None,
+            price_validity_period: None,
+            default_feeder: None,
+            max_assets: None,
+            display_decimals: None,
+            global_feeder_allowlist: None,
+            relay_contract: None,
+            feed_fee: None,
+            fee_collector: None,
+            on_stale: None,
+            maintenance_window: None,
+            price_limits: None,
+            feeder_max_feeds_per_window: None,
+            frozen_query_policy: None,
+            min_multiplier: Some(min_multiplier),
+            max_multiplier: Some(max_multiplier),
+            multiplier_enabled: None,
+            post_unpause_grace: None,
+        };
+    let set_multiplier = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, multiplier: Decimal| {
+        let msg = ExecuteMsg::SetMultipliers {
+            updates: vec![("maapl".to_string(), multiplier)],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg)
+    };
+
+    // unbounded by default
+    set_multiplier(&mut deps, Decimal::from_ratio(1_000_000u128, 1u128)).unwrap();
+
+    let msg = update_config(Decimal::percent(200), Decimal::percent(50));
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidMultiplierBounds {});
+
+    let msg = update_config(Decimal::percent(50), Decimal::percent(200));
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.min_multiplier, Some(Decimal::percent(50)));
+    assert_eq!(config.max_multiplier, Some(Decimal::percent(200)));
+
+    // bounds are inclusive
+    set_multiplier(&mut deps, Decimal::percent(50)).unwrap();
+    set_multiplier(&mut deps, Decimal::percent(200)).unwrap();
+
+    let res = set_multiplier(&mut deps, Decimal::percent(49)).unwrap_err();
+    assert_eq!(res, ContractError::MultiplierOutOfBounds {});
+    let res = set_multiplier(&mut deps, Decimal::percent(201)).unwrap_err();
+    assert_eq!(res, ContractError::MultiplierOutOfBounds {});
+
+    // tiers are held to the same bounds
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: Some(vec![
+            (Decimal::percent(1000), Decimal::percent(150)),
+            (Decimal::percent(10000), Decimal::percent(300)),
+        ]),
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::MultiplierOutOfBounds {});
+
+    // zero removes a bound
+    let msg = update_config(Decimal::zero(), Decimal::zero());
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    set_multiplier(&mut deps, Decimal::percent(1)).unwrap();
+    set_multiplier(&mut deps, Decimal::percent(1000)).unwrap();
+}
+
+#[test]
+fn price_effective_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::SetMultipliers {
+        updates: vec![("maapl".to_string(), Decimal::percent(200))],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::percent(150)),
+            ("mgogl".to_string(), Decimal::percent(600)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the rate carries the multiplier rather than the raw 150% feed, the
+    // effective price does not depend on the quote asset
+    for (quote_asset, rate) in [
+        ("uusd", Decimal::percent(300)),
+        ("mgogl", Decimal::percent(50)),
+    ] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: quote_asset.to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(price_res.rate, rate);
+        assert_eq!(price_res.ema_rate, rate);
+        assert_eq!(price_res.effective_price, Decimal::percent(300));
+    }
+}
+
+#[test]
+fn multiplier_disabled() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let set_multiplier = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, multiplier: Decimal| {
+        let msg = ExecuteMsg::SetMultipliers {
+            updates: vec![("maapl".to_string(), multiplier)],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg)
+    };
+    set_multiplier(&mut deps, Decimal::percent(200)).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::percent(150))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: Some(false),
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert!(!config.multiplier_enabled);
+
+    // only a multiplier of one can be set
+    let res = set_multiplier(&mut deps, Decimal::percent(200)).unwrap_err();
+    assert_eq!(res, ContractError::MultiplierDisabled {});
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: Some(vec![(Decimal::percent(1000), Decimal::percent(300))]),
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::MultiplierDisabled {});
+
+    // the stored multiplier is ignored, the effective price is the raw price
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Price {
+            base_asset: "maapl".to_string(),
+            quote_asset: "uusd".to_string(),
+            base: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::percent(150));
+    assert_eq!(price_res.effective_price, Decimal::percent(150));
+
+    set_multiplier(&mut deps, Decimal::one()).unwrap();
+}
+
+#[test]
+fn asset_action_count() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let action_count = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FullPrice {
+                asset_token: "maapl".to_string(),
+                explain: None,
+            },
+        )
+        .unwrap();
+        let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+        full_price_res.action_count
+    };
+
+    assert_eq!(action_count(&deps), 0u64);
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::one())],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(action_count(&deps), 1u64);
+
+    let msg = ExecuteMsg::FreezeAsset {
+        asset_token: "maapl".to_string(),
+        pause_reason: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(action_count(&deps), 2u64);
+
+    let msg = ExecuteMsg::SetMultipliers {
+        updates: vec![("maapl".to_string(), Decimal::percent(200))],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(action_count(&deps), 3u64);
+
+    let msg = ExecuteMsg::UnfreezeAsset {
+        asset_token: "maapl".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(action_count(&deps), 4u64);
+
+    let msg = ExecuteMsg::Heartbeat {
+        asset_token: "maapl".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(action_count(&deps), 5u64);
+
+    let msg = ExecuteMsg::SetLastUpdateTime {
+        asset_token: "maapl".to_string(),
+        time: mock_env().block.time.seconds() - 10,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(action_count(&deps), 6u64);
+}
+
+#[test]
+fn feed_price_monotone_increasing() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mindex".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let asset_config_msg = |monotone_increasing: bool| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "mindex".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: Some(monotone_increasing),
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        asset_config_msg(true),
+    )
+    .unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u64| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("mindex".to_string(), Decimal::from_ratio(price, 1u64))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+
+    feed(&mut deps, 100).unwrap();
+    // increase
+    feed(&mut deps, 101).unwrap();
+    // equal
+    feed(&mut deps, 101).unwrap();
+    // decrease
+    let res = feed(&mut deps, 100).unwrap_err();
+    assert_eq!(res, ContractError::PriceDecreased {});
+
+    let _res = execute(deps.as_mut(), mock_env(), info, asset_config_msg(false)).unwrap();
+    feed(&mut deps, 100).unwrap();
+}
+
+#[test]
+fn snapshot() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u64, seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u64))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+    };
+    let snapshot = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, label: &str| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1500);
+        let msg = ExecuteMsg::Snapshot {
+            label: label.to_string(),
+        };
+        execute(deps.as_mut(), env, mock_info("owner0000", &[]), msg)
+    };
+    let query_snapshot = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, label: &str| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Snapshot {
+                label: label.to_string(),
+            },
+        )
+    };
+
+    feed(&mut deps, 150, 1000);
+
+    let msg = ExecuteMsg::Snapshot {
+        label: "eod".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = snapshot(&mut deps, "eod").unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "snapshot"),
+            attr("label", "eod"),
+            attr("asset_count", "1"),
+        ]
+    );
+
+    feed(&mut deps, 160, 2000);
+
+    let res = query_snapshot(&deps, "eod").unwrap();
+    let snapshot_res: SnapshotResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        snapshot_res,
+        SnapshotResponse {
+            label: "eod".to_string(),
+            taken_at: 1500u64,
+            prices: vec![PricesResponseElem {
+                asset_token: "maapl".to_string(),
+                price: Decimal::from_ratio(150u128, 1u128),
+                last_updated_time: 1000u64,
+            }],
+        }
+    );
+
+    // only the latest snapshots are retained
+    for i in 0..10 {
+        snapshot(&mut deps, &format!("label{}", i)).unwrap();
+    }
+    assert!(query_snapshot(&deps, "eod").is_err());
+    assert!(query_snapshot(&deps, "label0").is_ok());
+}
+
+#[test]
+fn feed_price_peg() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "mstable".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let asset_config_msg = |peg: Decimal| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "mstable".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: Some(peg),
+        peg_tolerance: Some(Decimal::percent(2)),
+        stale_message: None,
+        subscription: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        asset_config_msg(Decimal::zero()),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::InvalidPeg {});
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        asset_config_msg(Decimal::one()),
+    )
+    .unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: Decimal| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("mstable".to_string(), price)],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+
+    feed(&mut deps, Decimal::percent(101)).unwrap();
+    feed(&mut deps, Decimal::percent(98)).unwrap();
+    let res = feed(&mut deps, Decimal::percent(105)).unwrap_err();
+    assert_eq!(res, ContractError::PriceOffPeg {});
+    let res = feed(&mut deps, Decimal::percent(97)).unwrap_err();
+    assert_eq!(res, ContractError::PriceOffPeg {});
+}
+
+#[test]
+fn query_price_frozen_policy() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::FreezeAsset {
+        asset_token: "maapl".to_string(),
+        pause_reason: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+    };
+
+    // the last price is returned by default
+    let res = price(&deps).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::from_ratio(150u128, 1u128));
+    assert!(price_res.frozen);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: Some(FrozenPolicy::Error),
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = price(&deps).unwrap_err();
+    assert_eq!(res, StdError::generic_err("asset is frozen"));
+}
+
+#[test]
+fn heartbeat() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env_at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    };
+    let msg = ExecuteMsg::Heartbeat {
+        asset_token: "maapl".to_string(),
+    };
+    let feeder_info = mock_info("addr0000", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1000),
+        feeder_info.clone(),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NeverFed {});
+
+    let feed_msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let _res = execute(deps.as_mut(), env_at(1000), feeder_info.clone(), feed_msg).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1050),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env_at(1050), feeder_info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "heartbeat"),
+            attr("asset_token", "maapl"),
+            attr("last_updated_time", "1050"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.price, Decimal::from_ratio(150u128, 1u128));
+    assert_eq!(full_price_res.prev_price, Decimal::zero());
+    assert_eq!(full_price_res.last_updated_time, 1050u64);
+
+    // fresh past the validity period of the feed
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: 1100u64,
+        },
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn batch_portfolio_value() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl", "mnflx"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    // mgogl is valued at its effective price
+    let msg = ExecuteMsg::SetMultipliers {
+        updates: vec![("mgogl".to_string(), Decimal::percent(200))],
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>,
+                prices: Vec<(&str, Decimal)>,
+                seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: prices
+                .into_iter()
+                .map(|(asset_token, price)| (asset_token.to_string(), price))
+                .collect(),
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+    };
+    feed(&mut deps, vec![("mnflx", Decimal::percent(300))], 900);
+    feed(
+        &mut deps,
+        vec![
+            ("maapl", Decimal::percent(250)),
+            ("mgogl", Decimal::percent(1000)),
+        ],
+        1000,
+    );
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BatchPortfolioValue {
+            portfolios: vec![
+                (
+                    "alice".to_string(),
+                    vec![
+                        ("maapl".to_string(), Uint128::from(100u128)),
+                        ("mgogl".to_string(), Uint128::from(3u128)),
+                    ],
+                ),
+                (
+                    "bob".to_string(),
+                    vec![("maapl".to_string(), Uint128::from(7u128))],
+                ),
+                ("carol".to_string(), vec![]),
+            ],
+        },
+    )
+    .unwrap();
+    let batch_res: BatchPortfolioValueResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        batch_res.totals,
+        vec![
+            ("alice".to_string(), Uint128::from(310u128)),
+            // 17.5 rounds down
+            ("bob".to_string(), Uint128::from(17u128)),
+            ("carol".to_string(), Uint128::zero()),
+        ]
+    );
+
+    // a stale holding fails the whole batch
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BatchPortfolioValue {
+            portfolios: vec![(
+                "alice".to_string(),
+                vec![("mnflx".to_string(), Uint128::from(1u128))],
+            )],
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "price of asset mnflx is stale")
+        }
+        _ => panic!("Must return generic error"),
+    }
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BatchPortfolioValue {
+            portfolios: vec![(
+                "alice".to_string(),
+                vec![("mtsla".to_string(), Uint128::from(1u128))],
+            )],
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "no price for asset mtsla"),
+        _ => panic!("Must return generic error"),
+    }
+
+    // huge holdings fail instead of overflowing
+    for holdings in [
+        vec![("mgogl".to_string(), Uint128::MAX)],
+        // each holding fits, their sum does not
+        vec![
+            ("maapl".to_string(), Uint128::MAX / Uint128::from(3u128)),
+            ("maapl".to_string(), Uint128::MAX / Uint128::from(3u128)),
+        ],
+    ] {
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BatchPortfolioValue {
+                portfolios: vec![("alice".to_string(), holdings)],
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "value overflow"),
+            _ => panic!("Must return generic error"),
+        }
+    }
+
+    // the bound counts holdings across every portfolio
+    let holdings = vec![("maapl".to_string(), Uint128::from(1u128)); 51];
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::BatchPortfolioValue {
+            portfolios: vec![
+                ("alice".to_string(), holdings.clone()),
+                ("bob".to_string(), holdings),
+            ],
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "at most 100 portfolio entries can be valued at once")
+        }
+        _ => panic!("Must return generic error"),
+    }
+}
+
+#[test]
+fn stale_message() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: Some(StaleResponse::Error),
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let update_stale_message = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>,
+                                stale_message: String| {
+        let msg = ExecuteMsg::UpdateAssetConfig {
+            asset_token: "maapl".to_string(),
+            ema_alpha: None,
+            slippage_per_unit: None,
+            subscribers: None,
+            price_band: None,
+            out_of_band: None,
+            basket: None,
+            price_multiplier: None,
+            multiplier_tiers: None,
+            max_deviation: None,
+            monotone_increasing: None,
+            peg: None,
+            peg_tolerance: None,
+            stale_message: Some(stale_message),
+            subscription: None,
+        };
+        let info = mock_info("owner0000", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg)
+    };
+
+    let res = update_stale_message(&mut deps, "a".repeat(257)).unwrap_err();
+    assert_eq!(res, ContractError::StaleMessageTooLong { max_length: 256 });
+
+    update_stale_message(&mut deps, "AAPL feed offline, contact ops".to_string()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![
+            ("maapl".to_string(), Decimal::from_ratio(150u128, 1u128)),
+            ("mgogl".to_string(), Decimal::from_ratio(2500u128, 1u128)),
+        ],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    env.block.time = Timestamp::from_seconds(1061u64);
+    let query_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, base_asset: &str| {
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Price {
+                base_asset: base_asset.to_string(),
+                quote_asset: "uusd".to_string(),
+                base: None,
+            },
+        )
+    };
+
+    let res = query_price(&deps, "maapl").unwrap_err();
+    assert_eq!(res, StdError::generic_err("AAPL feed offline, contact ops"));
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: 1061u64,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("AAPL feed offline, contact ops"));
+
+    // assets without a message keep the generic one
+    let res = query_price(&deps, "mgogl").unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+
+    // an empty message restores the generic one
+    update_stale_message(&mut deps, "".to_string()).unwrap();
+    let res = query_price(&deps, "maapl").unwrap_err();
+    assert_eq!(res, StdError::generic_err("price too old"));
+}
+
+#[test]
+fn migrate_to_feeder_group() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for (asset_token, feeder) in [("maapl", "feeder0000"), ("mgogl", "feeder0001")] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some(feeder.to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, feeder: &str, asset_token: &str| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(asset_token.to_string(), Decimal::percent(150))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(feeder, &[]), msg)
+    };
+
+    let msg = ExecuteMsg::MigrateToFeederGroup {
+        group: Some("group0000".to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_to_feeder_group"),
+            attr("group", "group0000"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.feeder_group, Some("group0000".to_string()));
+
+    // the group feeds every asset and the per-asset feeders no longer apply
+    feed(&mut deps, "group0000", "maapl").unwrap();
+    feed(&mut deps, "group0000", "mgogl").unwrap();
+    let res = feed(&mut deps, "feeder0000", "maapl").unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+    let res = feed(&mut deps, "feeder0001", "mgogl").unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    // migrating back restores the per-asset feeders
+    let msg = ExecuteMsg::MigrateToFeederGroup { group: None };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    feed(&mut deps, "feeder0000", "maapl").unwrap();
+    let res = feed(&mut deps, "feeder0000", "mgogl").unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+    let res = feed(&mut deps, "group0000", "maapl").unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn confirmed_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let confirmed_price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, min_updates: u64| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConfirmedPrice {
+                asset_token: "maapl".to_string(),
+                min_updates,
+            },
+        )
+    };
+
+    let mut env = mock_env();
+    for (seconds, price) in [(1000u64, 150u128), (1010u64, 151u128)] {
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(price, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        let info = mock_info("addr0000", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    let res = confirmed_price(&deps, 3u64).unwrap_err();
+    assert_eq!(res, StdError::generic_err("price fed 2 times, 3 required"));
+
+    let res = confirmed_price(&deps, 2u64).unwrap();
+    let confirmed_price_res: SafePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        confirmed_price_res,
+        SafePriceResponse {
+            price: Decimal::from_ratio(151u128, 1u128),
+            last_updated: 1010u64,
+        }
+    );
+}
+
+#[test]
+fn current_price_source() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("feeder0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: Some("relay0000".to_string()),
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let current_price_source = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CurrentPriceSource {
+                asset_token: "maapl".to_string(),
+            },
+        )
+        .unwrap();
+        let current_price_source_res: CurrentPriceSourceResponse = from_binary(&res).unwrap();
+        current_price_source_res
+    };
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, feeder: &str| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::percent(150))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(feeder, &[]), msg).unwrap();
+    };
+
+    assert_eq!(
+        current_price_source(&deps),
+        CurrentPriceSourceResponse {
+            asset_token: "maapl".to_string(),
+            set_by: None,
+            authorization: None,
+        }
+    );
+
+    feed(&mut deps, "feeder0000");
+    assert_eq!(
+        current_price_source(&deps),
+        CurrentPriceSourceResponse {
+            asset_token: "maapl".to_string(),
+            set_by: Some("feeder0000".to_string()),
+            authorization: Some(PriceAuthorization::Feeder),
+        }
+    );
+
+    feed(&mut deps, "relay0000");
+    assert_eq!(
+        current_price_source(&deps),
+        CurrentPriceSourceResponse {
+            asset_token: "maapl".to_string(),
+            set_by: Some("relay0000".to_string()),
+            authorization: Some(PriceAuthorization::Relay),
+        }
+    );
+
+    let msg = ExecuteMsg::MigrateToFeederGroup {
+        group: Some("group0000".to_string()),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    feed(&mut deps, "group0000");
+    assert_eq!(
+        current_price_source(&deps),
+        CurrentPriceSourceResponse {
+            asset_token: "maapl".to_string(),
+            set_by: Some("group0000".to_string()),
+            authorization: Some(PriceAuthorization::Group),
+        }
+    );
+}
+
+#[test]
+fn register_reserved_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: Some("ukrw".to_string()),
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let register = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, asset_token: &str| {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg)
+    };
+
+    for asset_token in ["uusd", "UUSD", "ukrw"] {
+        let res = register(&mut deps, asset_token).unwrap_err();
+        assert_eq!(res, ContractError::ReservedToken {});
+    }
+
+    // a token can not be moved onto a base asset either
+    register(&mut deps, "maapl").unwrap();
+    let msg = ExecuteMsg::UpdateToken {
+        asset_token: "maapl".to_string(),
+        new_asset_token: "uusd".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::ReservedToken {});
+
+    // nor onto a token registered for another asset
+    register(&mut deps, "mgogl").unwrap();
+    let msg = ExecuteMsg::UpdateToken {
+        asset_token: "maapl".to_string(),
+        new_asset_token: "mgogl".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::AssetAlreadyRegistered {});
+}
+
+#[test]
+fn subscribe() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: None,
+        fee_collector: Some("collector0000".to_string()),
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let subscribe = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, funds: &[cosmwasm_std::Coin]| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1000u64);
+        let msg = ExecuteMsg::Subscribe {
+            asset_token: "maapl".to_string(),
+        };
+        execute(deps.as_mut(), env, mock_info("sub0000", funds), msg)
+    };
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap()
+    };
+
+    let res = subscribe(&mut deps, &[coin(100u128, "uusd")]).unwrap_err();
+    assert_eq!(res, ContractError::SubscriptionNotOffered {});
+
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: Some((coin(100u128, "uusd"), 3600u64)),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = subscribe(&mut deps, &[coin(99u128, "uusd"), coin(100u128, "uluna")]).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientSubscriptionFee {
+            fee: coin(100u128, "uusd"),
+        }
+    );
+
+    let res = subscribe(&mut deps, &[coin(100u128, "uusd")]).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "collector0000".to_string(),
+            amount: vec![coin(100u128, "uusd")],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "subscribe"),
+            attr("asset_token", "maapl"),
+            attr("subscriber", "sub0000"),
+            attr("expires_at", "4600"),
+        ]
+    );
+
+    // notified until the subscription expires
+    let res = feed(&mut deps, 4599u64);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "sub0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&PriceHookMsg::PriceUpdate {
+                    asset_token: "maapl".to_string(),
+                    price: Decimal::from_ratio(150u128, 1u128),
+                    last_updated_time: 4599u64,
+                })
+                .unwrap(),
+            }),
+            PRICE_HOOK_REPLY_ID,
+        )
+        .with_gas_limit(PRICE_HOOK_GAS_LIMIT)]
+    );
+
+    // a subscriber failing the hook, e.g. a wallet, does not revert the feed
+    let msg = Reply {
+        id: PRICE_HOOK_REPLY_ID,
+        result: ContractResult::Err("unknown variant `price_update`".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "price_hook_failed"),
+            attr("error", "unknown variant `price_update`"),
+        ]
+    );
+
+    // owner subscribers and paid subscribers share the subscriber limit
+    let update_subscribers = |count: usize| ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: Some((0..count).map(|i| format!("owner_sub{:04}", i)).collect()),
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(4599u64);
+    let info = mock_info("owner0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        update_subscribers(10),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::TooManySubscribers {
+            max_subscribers: 10
+        }
+    );
+    let _res = execute(deps.as_mut(), env, info.clone(), update_subscribers(9)).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(4599u64);
+    let msg = ExecuteMsg::Subscribe {
+        asset_token: "maapl".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("sub0001", &[coin(100u128, "uusd")]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::TooManySubscribers {
+            max_subscribers: 10
+        }
+    );
+    let _res = execute(deps.as_mut(), mock_env(), info, update_subscribers(0)).unwrap();
+
+    let res = feed(&mut deps, 4600u64);
+    assert_eq!(res.messages, vec![]);
+
+    // huge durations never expire instead of overflowing
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: None,
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: Some((coin(100u128, "uusd"), u64::MAX)),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    for _ in 0..2 {
+        let res = subscribe(&mut deps, &[coin(100u128, "uusd")]).unwrap();
+        assert_eq!(
+            res.attributes.last().unwrap(),
+            &attr("expires_at", u64::MAX.to_string())
+        );
+    }
+}
+
+#[test]
+fn max_price_validity_period() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: u64::MAX,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: None,
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000u64);
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("maapl".to_string(), Decimal::from_ratio(150u128, 1u128))],
+        alt_prices: None,
+        source: None,
+        idempotency_key: None,
+        only_if_changed: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // the freshness window saturates instead of overflowing
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SafePrice {
+            asset_token: "maapl".to_string(),
+            now: u64::MAX,
+        },
+    )
+    .unwrap();
+    let safe_price_res: SafePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(safe_price_res.last_updated, 1000u64);
+
+    let msg = ExecuteMsg::RemoveAsset {
+        asset_token: "maapl".to_string(),
+        force: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::FreshPrice {});
+}
+
+#[test]
+fn migrate_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    // a legacy config is converted
+    let mut legacy_store: Singleton<LegacyConfig> = singleton(&mut deps.storage, KEY_CONFIG);
+    legacy_store
+        .save(&LegacyConfig {
+            owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+            base_asset: "uusd".to_string(),
+        })
+        .unwrap();
+
+    let msg = MigrateMsg {
+        price_validity_period: 60u64,
+    };
+    let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.owner, "owner0000");
+    assert_eq!(config_res.base_asset, "uusd");
+    assert_eq!(config_res.price_validity_period, 60u64);
+
+    // a current config is left untouched
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: Some("uluna".to_string()),
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: Some("relay0000".to_string()),
+        feed_fee: None,
+        fee_collector: None,
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = MigrateMsg {
+        price_validity_period: 120u64,
+    };
+    let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.alt_base_asset, Some("uluna".to_string()));
+    assert_eq!(config_res.relay_contract, Some("relay0000".to_string()));
+    assert_eq!(config_res.price_validity_period, 60u64);
+}
+
+#[test]
+fn migrate_price_ema() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset_token in ["maapl", "mgogl"] {
+        let msg = ExecuteMsg::RegisterAsset {
+            asset_token: asset_token.to_string(),
+            feeder: Some("addr0000".to_string()),
+            feeder_pubkey: None,
+            decimals: None,
+            fetch_decimals: false,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let feed = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, price: u64| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![
+                ("maapl".to_string(), Decimal::from_ratio(price, 1u64)),
+                ("mgogl".to_string(), Decimal::from_ratio(price, 1u64)),
+            ],
+            alt_prices: None,
+            source: None,
+            idempotency_key: None,
+            only_if_changed: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    };
+    let price = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base_asset: "maapl".to_string(),
+                quote_asset: "mgogl".to_string(),
+                base: None,
+            },
+        )
+        .unwrap();
+        let price_res: PriceResponse = from_binary(&res).unwrap();
+        (price_res.rate, price_res.ema_rate)
+    };
+    feed(&mut deps, 100);
+
+    // prices stored before the EMA existed load with a zero EMA
+    for asset_token in ["maapl", "mgogl"] {
+        let asset_token_raw = deps.api.addr_canonicalize(asset_token).unwrap();
+        let mut price_info = read_price(&deps.storage, &asset_token_raw).unwrap();
+        price_info.ema = Decimal::zero();
+        store_price(&mut deps.storage, &asset_token_raw, &price_info).unwrap();
+    }
+    assert_eq!(price(&deps), (Decimal::one(), Decimal::zero()));
+
+    let msg = MigrateMsg {
+        price_validity_period: 60u64,
+    };
+    let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(price(&deps), (Decimal::one(), Decimal::one()));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FullPrice {
+            asset_token: "maapl".to_string(),
+            explain: None,
+        },
+    )
+    .unwrap();
+    let full_price_res: FullPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(full_price_res.ema, Decimal::from_ratio(100u128, 1u128));
+
+    // a feed onto an unseeded EMA seeds it rather than averaging with zero
+    let msg = ExecuteMsg::UpdateAssetConfig {
+        asset_token: "maapl".to_string(),
+        ema_alpha: Some(Decimal::percent(50)),
+        slippage_per_unit: None,
+        subscribers: None,
+        price_band: None,
+        out_of_band: None,
+        basket: None,
+        price_multiplier: None,
+        multiplier_tiers: None,
+        max_deviation: None,
+        monotone_increasing: None,
+        peg: None,
+        peg_tolerance: None,
+        stale_message: None,
+        subscription: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let asset_token_raw = deps.api.addr_canonicalize("maapl").unwrap();
+    let mut price_info = read_price(&deps.storage, &asset_token_raw).unwrap();
+    price_info.ema = Decimal::zero();
+    store_price(&mut deps.storage, &asset_token_raw, &price_info).unwrap();
+
+    feed(&mut deps, 200);
+    let price_info = read_price(&deps.storage, &asset_token_raw).unwrap();
+    assert_eq!(price_info.ema, Decimal::from_ratio(200u128, 1u128));
+}
+
+#[test]
+fn feed_fee_on_signed_feed_and_heartbeat() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "uusd".to_string(),
+        price_validity_period: 60u64,
+        allow_reset_all: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let feeder_pubkey = signing_key.verifying_key().to_bytes().to_vec();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterAsset {
+        asset_token: "maapl".to_string(),
+        feeder: Some("addr0000".to_string()),
+        feeder_pubkey: Some(Binary::from(feeder_pubkey)),
+        decimals: None,
+        fetch_decimals: false,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        alt_base_asset: None,
+        price_validity_period: None,
+        default_feeder: None,
+        max_assets: None,
+        display_decimals: None,
+        global_feeder_allowlist: None,
+        relay_contract: None,
+        feed_fee: Some(coin(100u128, "uusd")),
+        fee_collector: Some("collector0000".to_string()),
+        on_stale: None,
+        maintenance_window: None,
+        price_limits: None,
+        feeder_max_feeds_per_window: None,
+        frozen_query_policy: None,
+        min_multiplier: None,
+        max_multiplier: None,
+        multiplier_enabled: None,
+        post_unpause_grace: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let price = Decimal::from_ratio(12u128, 10u128);
+    let sign_bytes = feed_price_sign_bytes(env.contract.address.as_str(), "maapl", price, 1u64);
+    let signature: Signature = signing_key.sign(&sign_bytes);
+    let msg = ExecuteMsg::FeedPriceSigned {
+        asset_token: "maapl".to_string(),
+        price,
+        nonce: 1u64,
+        signature: Binary::from(signature.as_ref()),
+    };
+    let fee_message = SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "collector0000".to_string(),
+        amount: vec![coin(100u128, "uusd")],
+    }));
+
+    // the relayer submitting the signature pays
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer0000", &[coin(99u128, "uusd")]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientFeedFee {
+            fee: coin(100u128, "uusd"),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer0000", &[coin(100u128, "uusd")]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![fee_message.clone()]);
+
+    let msg = ExecuteMsg::Heartbeat {
+        asset_token: "maapl".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientFeedFee {
+            fee: coin(100u128, "uusd"),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &[coin(100u128, "uusd")]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![fee_message]);
 }
@@ -88,6 +88,7 @@ pub fn query_price(
         msg: to_binary(&OracleQueryMsg::Price {
             base_asset,
             quote_asset,
+            base: None,
         })?,
     }))?;
 
@@ -115,8 +115,16 @@ impl WasmMockQuerier {
                     quote_asset: _,
                 } => SystemResult::Ok(ContractResult::from(to_binary(&PriceResponse {
                     rate: self.oracle_price,
+                    ema_rate: self.oracle_price,
                     last_updated_base: 100,
                     last_updated_quote: 100,
+                    source_base: None,
+                    source_quote: None,
+                    frozen: false,
+                    never_fed: false,
+                    stale: false,
+                    effective_price: self.oracle_price,
+                    warming_up: false,
                 }))),
                 MockQueryMsg::Balance { address: _ } => {
                     SystemResult::Ok(ContractResult::from(to_binary(&cw20::BalanceResponse {
@@ -2,12 +2,88 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::common::OrderBy;
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Binary, Coin, Decimal, Uint128};
+
+/// How the sender of a base_asset price was authorized to set it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAuthorization {
+    /// the asset's feeder, directly or through a signed feed
+    Feeder,
+    /// the feeder group
+    Group,
+    /// the relay contract
+    Relay,
+    /// the owner, registering or importing the price
+    Owner,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
     pub base_asset: String,
+    /// seconds after its last update a price stops being fresh
+    pub price_validity_period: u64,
+    /// enables ResetAllPrices; defaults to false and cannot be changed later
+    pub allow_reset_all: Option<bool>,
+}
+
+/// Config fields changed by UpdateConfig or ScheduleConfig, None leaves the
+/// current value unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ConfigChanges {
+    pub owner: Option<String>,
+    pub alt_base_asset: Option<String>,
+    pub price_validity_period: Option<u64>,
+    pub default_feeder: Option<String>,
+    pub max_assets: Option<u64>,
+    pub display_decimals: Option<u32>,
+    pub global_feeder_allowlist: Option<Vec<String>>,
+    pub relay_contract: Option<String>,
+    pub feed_fee: Option<Coin>,
+    pub fee_collector: Option<String>,
+    pub on_stale: Option<StaleResponse>,
+    pub maintenance_window: Option<(u64, u64)>,
+    pub price_limits: Option<(Decimal, Decimal)>,
+    pub feeder_max_feeds_per_window: Option<(u32, u64)>,
+    pub frozen_query_policy: Option<FrozenPolicy>,
+    pub min_multiplier: Option<Decimal>,
+    pub max_multiplier: Option<Decimal>,
+    pub multiplier_enabled: Option<bool>,
+    pub post_unpause_grace: Option<u64>,
+}
+
+/// Behaviour of the Price query for prices older than the validity period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleResponse {
+    /// fail the query
+    Error,
+    /// return the stale rate with `stale` set
+    #[default]
+    Flag,
+}
+
+/// Behaviour of the Price query for frozen assets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FrozenPolicy {
+    /// fail the query
+    Error,
+    /// return the last rate with `frozen` set
+    #[default]
+    ReturnLast,
+}
+
+/// Handling of base_asset prices fed outside an asset's price band
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutOfBand {
+    /// store the nearest bound of the band instead
+    Clamp,
+    /// fail the feed
+    #[default]
+    Reject,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -15,14 +91,247 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     UpdateConfig {
         owner: Option<String>,
+        /// secondary denom prices can be fed in, changing it clears every
+        /// stored alt price
+        alt_base_asset: Option<String>,
+        price_validity_period: Option<u64>,
+        /// feeder of assets registered without one, an empty address
+        /// removes it
+        default_feeder: Option<String>,
+        /// maximum number of registered assets, unlimited by default,
+        /// zero removes the cap
+        max_assets: Option<u64>,
+        /// decimal places kept in Price query rates, at most 18, which
+        /// keeps full precision
+        display_decimals: Option<u32>,
+        /// when non-empty, a feeder must also be listed to feed the assets it
+        /// is registered on; an empty list allows every registered feeder
+        global_feeder_allowlist: Option<Vec<String>>,
+        /// relay allowed to feed every asset regardless of its feeder,
+        /// an empty address removes it
+        relay_contract: Option<String>,
+        /// funds every feed, signed feed and heartbeat must send, forwarded
+        /// to the fee collector, a zero amount removes the fee
+        feed_fee: Option<Coin>,
+        /// receives feed fees, feeds are rejected while a feed fee is set
+        /// without a collector, an empty address removes it
+        fee_collector: Option<String>,
+        /// how the Price query answers when either price is stale
+        on_stale: Option<StaleResponse>,
+        /// [start, end) block times during which feeds are rejected,
+        /// an empty window (start >= end) disables it
+        maintenance_window: Option<(u64, u64)>,
+        /// (min, max) absolute price accepted from any feed, inclusive,
+        /// a zero max removes the limits
+        price_limits: Option<(Decimal, Decimal)>,
+        /// (count, window seconds) capping the feed messages each feeder can
+        /// send in any rolling window, a zero count or window disables it
+        feeder_max_feeds_per_window: Option<(u32, u64)>,
+        /// how the Price query answers when either asset is frozen
+        frozen_query_policy: Option<FrozenPolicy>,
+        /// lowest price multiplier that can be set, zero removes the bound
+        min_multiplier: Option<Decimal>,
+        /// highest price multiplier that can be set, zero removes the bound
+        max_multiplier: Option<Decimal>,
+        /// false quotes every price unscaled and rejects setting any
+        /// multiplier other than one, enabled by default
+        multiplier_enabled: Option<bool>,
+        /// seconds after unpausing during which the Price query reports
+        /// `warming_up`, feeds are accepted throughout
+        post_unpause_grace: Option<u64>,
+    },
+    /// Owner only, queues config changes applied by the first message
+    /// executed at or after `effective_at`, replacing any queued changes.
+    /// Changes that no longer apply by then are dropped
+    ScheduleConfig {
+        changes: ConfigChanges,
+        effective_at: u64,
     },
-    /// Used to register new asset or to update feeder
+    /// Applies the scheduled config changes once they are effective
+    ApplyScheduledConfig {},
+    /// Used to register new asset or to update feeder,
+    /// falls back to the default feeder when feeder is omitted
     RegisterAsset {
         asset_token: String,
+        feeder: Option<String>,
+        /// compressed or uncompressed secp256k1 pubkey used to verify FeedPriceSigned
+        feeder_pubkey: Option<Binary>,
+        decimals: Option<u8>,
+        /// read decimals from the token contract instead of trusting `decimals`,
+        /// defaults to false when omitted
+        #[serde(default)]
+        fetch_decimals: bool,
+    },
+    /// Owner only, registers the asset and records its first price at the
+    /// current block time, so it is never left without a price
+    RegisterAndFeed {
+        asset_token: String,
+        feeder: Option<String>,
+        price: Decimal,
+    },
+    /// Replaces the feeders of registered assets, failing the whole batch
+    /// if any asset is not registered. Feeder pubkeys are cleared
+    UpdateFeeders {
+        updates: Vec<(String, String)>,
+    },
+    /// Removes a registered asset. Assets with a fresh price are likely
+    /// still in use, so removing them requires force. Components of an
+    /// index basket can not be removed
+    RemoveAsset {
+        asset_token: String,
+        force: bool,
+    },
+    /// Moves a registered asset, with its feeder, price and settings, to the
+    /// token's new address, updating every index basket it is a component of
+    UpdateToken {
+        asset_token: String,
+        new_asset_token: String,
+    },
+    /// Rejects every feed from the feeder, across all of its assets, until resumed
+    SuspendFeeder {
         feeder: String,
     },
+    ResumeFeeder {
+        feeder: String,
+    },
+    /// Stops accepting feeds for the asset, queries keep returning its last price
+    FreezeAsset {
+        asset_token: String,
+        /// shown in the FullPrice query until the asset is unfrozen
+        pause_reason: Option<String>,
+    },
+    UnfreezeAsset {
+        asset_token: String,
+    },
+    /// Owner only, pausing rejects price feeds for every asset
+    SetPaused {
+        paused: bool,
+        reason: Option<String>,
+    },
+    /// Owner only, hands feeding of every asset to the group contract in
+    /// place of the per-asset feeders. None migrates back to them
+    MigrateToFeederGroup {
+        group: Option<String>,
+    },
+    /// Updates per-asset settings; None leaves the current value unchanged
+    UpdateAssetConfig {
+        asset_token: String,
+        /// smoothing factor of the price EMA, in (0, 1]
+        ema_alpha: Option<Decimal>,
+        /// price impact per unit traded, applied by the ExecutionPrice query
+        slippage_per_unit: Option<Decimal>,
+        /// contracts sent a `PriceHookMsg` on every base_asset price feed,
+        /// replaces the current list
+        subscribers: Option<Vec<String>>,
+        /// (min, max) accepted base_asset price
+        price_band: Option<(Decimal, Decimal)>,
+        /// what happens to base_asset prices fed outside the price band
+        out_of_band: Option<OutOfBand>,
+        /// (component asset_token, units) making up an index asset,
+        /// whose price is the sum of units * component price
+        basket: Option<Vec<(String, Decimal)>>,
+        /// scales fed prices in every priced query and price hook, stored
+        /// and FullPrice prices stay unscaled
+        price_multiplier: Option<Decimal>,
+        /// (threshold, multiplier) tiers, a fed price uses the multiplier of
+        /// the highest threshold it reaches, below every threshold it falls
+        /// back to price_multiplier. Replaces the current tiers
+        multiplier_tiers: Option<Vec<(Decimal, Decimal)>>,
+        /// maximum relative change of a fed base_asset price from the
+        /// reference price, or from the current price when none is pinned
+        max_deviation: Option<Decimal>,
+        /// rejects base_asset feeds below the current price
+        monotone_increasing: Option<bool>,
+        /// base_asset price a pegged asset is expected to trade at
+        peg: Option<Decimal>,
+        /// maximum relative distance of a fed price from the peg
+        peg_tolerance: Option<Decimal>,
+        /// error returned instead of the generic one when the asset's price
+        /// is stale, an empty message restores the generic one
+        stale_message: Option<String>,
+        /// (fee, duration in seconds) of a Subscribe, a zero duration stops
+        /// offering subscriptions
+        subscription: Option<(Coin, u64)>,
+    },
+    /// Owner only, stores a copy of every asset's base_asset price under the
+    /// label, replacing a snapshot with the same label
+    Snapshot {
+        label: String,
+    },
+    /// Owner only, sets the price_multiplier of every listed asset,
+    /// failing the whole batch on any unregistered asset
+    SetMultipliers {
+        updates: Vec<(String, Decimal)>,
+    },
+    /// Owner only, pins the price feeds are deviation-checked against,
+    /// None unpins it
+    SetReferencePrice {
+        asset_token: String,
+        price: Option<Decimal>,
+    },
+    /// Feeds every component price of an index asset and recomputes
+    /// its price in the same transaction
+    FeedBasket {
+        index_token: String,
+        components: Vec<(String, Decimal)>,
+    },
+    /// Feeds prices quoted in base_asset and, optionally, in alt_base_asset
     FeedPrice {
         prices: Vec<(String, Decimal)>,
+        alt_prices: Option<Vec<(String, Decimal)>>,
+        /// Venue the base_asset prices were sourced from, informational only
+        source: Option<String>,
+        /// Resubmitting the last key used by the feeder is a no-op,
+        /// so relayers can safely retry
+        idempotency_key: Option<String>,
+        /// Skips prices within this relative change of the stored price,
+        /// leaving their last updated time untouched
+        only_if_changed: Option<Decimal>,
+    },
+    /// Feeds a price signed off-chain by the feeder key, submittable by any relayer.
+    /// The signature is over sha256("{oracle}:{asset_token}:{price}:{nonce}") and
+    /// nonce must be greater than the last one used for the asset
+    FeedPriceSigned {
+        asset_token: String,
+        price: Decimal,
+        nonce: u64,
+        signature: Binary,
+    },
+    /// Feeder only, refreshes the last updated time of the asset's
+    /// base_asset price without changing it
+    Heartbeat {
+        asset_token: String,
+    },
+    /// Adds the sender to the asset's subscribers for the asset's
+    /// subscription duration, paying its subscription fee. Subscribing again
+    /// extends the subscription
+    Subscribe {
+        asset_token: String,
+    },
+    /// Zeroes every asset price, only available when allow_reset_all is set
+    ResetAllPrices {},
+    /// Overwrites the last updated time of the asset's base_asset price, used to
+    /// correct timestamps lost during imports or migrations
+    SetLastUpdateTime {
+        asset_token: String,
+        time: u64,
+    },
+    /// Copies the base_asset prices of registered assets from a predecessor
+    /// oracle, keeping their last updated time. Assets it can not price are skipped
+    ImportPrices {
+        source_oracle: String,
+        asset_tokens: Vec<String>,
+    },
+}
+
+/// Executed on the subscribers of an asset whenever its base_asset price is fed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceHookMsg {
+    PriceUpdate {
+        asset_token: String,
+        price: Decimal,
+        last_updated_time: u64,
     },
 }
 
@@ -33,15 +342,146 @@ pub enum QueryMsg {
     Feeder {
         asset_token: String,
     },
+    /// Rate of base_asset in quote_asset, both sides read in the `base`
+    /// denom, which must be the base_asset or the alt_base_asset. When
+    /// omitted it is the alt_base_asset if either side is the alt_base_asset,
+    /// otherwise the base_asset
     Price {
         base_asset: String,
         quote_asset: String,
+        base: Option<String>,
     },
     Prices {
         start_after: Option<String>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    /// Returns asset prices as the Price query would quote them in base_asset,
+    /// flagged stale when not fed within the validity period before `now`
+    AllEffectivePrices {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        now: u64,
+    },
+    /// Returns every distinct feeder with the number of assets it feeds
+    Feeders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AssetExists {
+        asset_token: String,
+    },
+    /// Returns the config along with the registered asset tokens, capped at 100
+    Overview {},
+    /// Returns the registered asset tokens starting with `prefix`, at most 30
+    AssetTokensWithPrefix {
+        prefix: String,
+        limit: u32,
+    },
+    /// Returns the assets with the oldest prices first, at most 10
+    MostStale {
+        now: u64,
+        limit: u32,
+    },
+    /// Returns the base_asset price of the asset, erroring when it was not
+    /// updated within the price validity period as of `now`
+    SafePrice {
+        asset_token: String,
+        now: u64,
+    },
+    /// Same as SafePrice with a caller-chosen `max_age` in seconds instead of
+    /// the price validity period
+    PriceIfFresh {
+        asset_token: String,
+        now: u64,
+        max_age: u64,
+    },
+    /// Same as SafePrice, requiring the base_asset price to have been fed at
+    /// least `min_updates` times instead of being fresh
+    ConfirmedPrice {
+        asset_token: String,
+        min_updates: u64,
+    },
+    /// Returns the price of the first asset in terms of the last one, chained
+    /// through each consecutive pair of the path. Every hop must be fresh
+    /// as of the current block
+    PathPrice {
+        path: Vec<String>,
+        /// includes the rate of every hop in the response
+        explain: Option<bool>,
+    },
+    /// Returns the price of the asset in terms of `via`, an asset fed against
+    /// base_asset such as a native denom, same as a PathPrice of [asset, via]
+    PriceInDenom {
+        asset_token: String,
+        via: String,
+    },
+    /// Returns the mean base_asset price of every asset with a fresh, non-zero
+    /// price as of the current block
+    MarketIndex {},
+    /// Compares when the base_asset prices of two assets were last updated
+    UpdateTimeDiff {
+        a: String,
+        b: String,
+    },
+    /// Returns the base_asset prices of the given assets keyed by asset token,
+    /// omitting unregistered ones
+    PricesMap {
+        asset_tokens: Vec<String>,
+    },
+    /// Returns a 0-100 health score for the asset as of `now`, see
+    /// `HealthScoreResponse`
+    HealthScore {
+        asset_token: String,
+        now: u64,
+    },
+    /// Returns the base_asset price of the asset including the price impact
+    /// of trading `size` units: price * (1 + slippage_per_unit * size)
+    ExecutionPrice {
+        asset_token: String,
+        size: Uint128,
+    },
+    /// Returns the Price rate scaled by 10^decimals and rounded down, at most 18 decimals
+    QuotedPrice {
+        base_asset: String,
+        quote_asset: String,
+        decimals: u8,
+    },
+    /// Returns the assets whose latest base_asset price moved by more than
+    /// `threshold` relative to their previous price
+    BigMovers {
+        threshold: Decimal,
+    },
+    /// Returns a Merkle root committing to every asset's latest base_asset
+    /// price, see `PriceRootResponse`
+    PriceRoot {},
+    Snapshot {
+        label: String,
+    },
+    /// Returns the last block time at which the asset's price is still fresh
+    StaleAt {
+        asset_token: String,
+    },
+    /// Returns the address that set the asset's current base_asset price
+    /// and how it was authorized
+    CurrentPriceSource {
+        asset_token: String,
+    },
+    /// Returns the owner as hex-encoded canonical address bytes, for auditing
+    RawConfig {},
+    PauseStatus {},
+    /// Returns everything stored for the asset's price, the Price query
+    /// stays minimal for backward compatibility
+    FullPrice {
+        asset_token: String,
+        /// includes the contribution of every basket component of an index
+        explain: Option<bool>,
+    },
+    /// Values each labeled portfolio of (asset_token, amount) holdings in
+    /// base_asset at the effective price, failing on any stale or unknown asset
+    BatchPortfolioValue {
+        portfolios: Vec<(String, Vec<(String, Uint128)>)>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -49,6 +489,65 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub owner: String,
     pub base_asset: String,
+    pub alt_base_asset: Option<String>,
+    pub price_validity_period: u64,
+    pub allow_reset_all: bool,
+    pub default_feeder: Option<String>,
+    pub max_assets: Option<u64>,
+    pub display_decimals: Option<u32>,
+    pub global_feeder_allowlist: Vec<String>,
+    pub relay_contract: Option<String>,
+    pub feed_fee: Option<Coin>,
+    pub fee_collector: Option<String>,
+    pub on_stale: StaleResponse,
+    pub maintenance_window: Option<(u64, u64)>,
+    pub price_limits: Option<(Decimal, Decimal)>,
+    pub feeder_max_feeds_per_window: Option<(u32, u64)>,
+    pub frozen_query_policy: FrozenPolicy,
+    pub min_multiplier: Option<Decimal>,
+    pub max_multiplier: Option<Decimal>,
+    pub multiplier_enabled: bool,
+    pub post_unpause_grace: u64,
+    pub feeder_group: Option<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotResponse {
+    pub label: String,
+    pub taken_at: u64,
+    pub prices: Vec<PricesResponseElem>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StaleAtResponse {
+    /// last updated time + price validity period, 0 if never fed
+    pub stale_at: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurrentPriceSourceResponse {
+    pub asset_token: String,
+    /// none before the first feed
+    pub set_by: Option<String>,
+    pub authorization: Option<PriceAuthorization>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+    pub paused: bool,
+    pub reason: Option<String>,
+    /// block time of the last SetPaused, 0 if never called
+    pub since: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawConfigResponse {
+    /// lowercase hex of the canonical owner address
+    pub owner: String,
+    pub base_asset: String,
 }
 
 // We define a custom struct for each query response
@@ -61,9 +560,42 @@ pub struct FeederResponse {
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceResponse {
+    /// base/quote with each side's multiplier applied. Before multipliers
+    /// this was the raw fed ratio, callers wanting that must divide the
+    /// multiplier back out
     pub rate: Decimal,
+    /// ema of base/quote with the same multipliers applied as `rate`
+    pub ema_rate: Decimal,
     pub last_updated_base: u64,
     pub last_updated_quote: u64,
+    pub source_base: Option<String>,
+    pub source_quote: Option<String>,
+    /// true when either asset is frozen, so the rate may be stale
+    pub frozen: bool,
+    /// true when either asset is registered but was never fed, the rate is
+    /// then zero or undefined rather than a real price
+    pub never_fed: bool,
+    /// true when either price is older than the price validity period
+    pub stale: bool,
+    /// price of base_asset with its multiplier applied, in the denom both
+    /// sides are read in
+    pub effective_price: Decimal,
+    /// true within the post unpause grace period, prices may still be
+    /// catching up after the pause
+    pub warming_up: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedersResponseElem {
+    pub feeder: String,
+    pub asset_count: u32,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedersResponse {
+    pub feeders: Vec<FeedersResponseElem>,
 }
 
 // We define a custom struct for each query response
@@ -80,6 +612,178 @@ pub struct PricesResponse {
     pub prices: Vec<PricesResponseElem>,
 }
 
-/// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct EffectivePricesResponseElem {
+    pub asset_token: String,
+    pub effective_price: Decimal,
+    pub is_stale: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllEffectivePricesResponse {
+    pub prices: Vec<EffectivePricesResponseElem>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchPortfolioValueResponse {
+    /// (label, total value) in request order, rounded down
+    pub totals: Vec<(String, Uint128)>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetExistsResponse {
+    pub exists: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OverviewResponse {
+    pub config: ConfigResponse,
+    pub asset_tokens: Vec<String>,
+    /// true when more assets are registered than returned
+    pub truncated: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MostStaleResponseElem {
+    pub asset_token: String,
+    pub last_updated_time: u64,
+    pub age: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MostStaleResponse {
+    pub assets: Vec<MostStaleResponseElem>,
+}
+
+// We define a custom struct for each query response
+/// score = 100 - staleness penalty - deviation penalty, where
+/// - staleness penalty = 50 * age / price_validity_period, capped at 50
+/// - deviation penalty = 5 per 1% change from the previous price, capped at 50
+///
+/// Assets that were never fed score 0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HealthScoreResponse {
+    pub score: u8,
+}
+
+// We define a custom struct for each query response
+/// Leaves are sha256("{asset_token},{price},{last_updated_time}") sorted by
+/// asset_token. Each level hashes sha256(left || right) pairwise, carrying an
+/// unpaired last node up unchanged. No assets give 32 zero bytes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceRootResponse {
+    pub root: Binary,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PathPriceResponse {
+    pub rate: Decimal,
+    /// oldest update among the assets on the path
+    pub last_updated: u64,
+    /// "base/quote = rate" for every hop, only when explain is set
+    pub derivation: Option<Vec<String>>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketIndexResponse {
+    /// zero when no asset is included
+    pub average_price: Decimal,
+    pub asset_count: u32,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpdateTimeDiffResponse {
+    /// seconds between the two last updates
+    pub diff: u64,
+    /// the more recently updated asset, none when both were updated together
+    pub fresher: Option<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PricesMapResponse {
+    /// (asset_token, price) pairs sorted by asset token, as serde-json-wasm
+    /// cannot serialize maps
+    pub prices: Vec<(String, Decimal)>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SafePriceResponse {
+    pub price: Decimal,
+    pub last_updated: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutionPriceResponse {
+    pub price: Decimal,
+    pub last_updated: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetTokensResponse {
+    pub asset_tokens: Vec<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BigMoversResponse {
+    pub asset_tokens: Vec<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FullPriceResponse {
+    pub asset_token: String,
+    pub feeder: String,
+    pub decimals: Option<u8>,
+    pub price: Decimal,
+    pub ema: Decimal,
+    pub prev_price: Decimal,
+    pub last_updated_time: u64,
+    pub source: Option<String>,
+    pub alt_price: Option<Decimal>,
+    pub alt_last_updated_time: u64,
+    /// average seconds between base_asset price feeds, none before the second feed
+    pub avg_update_interval: Option<u64>,
+    pub frozen: bool,
+    pub pause_reason: Option<String>,
+    /// true when the latest price was clamped to the price band
+    pub was_clamped: bool,
+    /// "component: units * price = contribution" for every basket component,
+    /// only when explain is set
+    pub derivation: Option<Vec<String>>,
+    /// number of feeds, heartbeats, freezes and config changes applied to
+    /// the asset, including owner set update times
+    pub action_count: u64,
+    /// number of base_asset feeds since registration or the last reset
+    pub update_count: u64,
+    /// sender of the base_asset price, none before the first feed
+    pub set_by: Option<String>,
+    pub authorization: Option<PriceAuthorization>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QuotedPriceResponse {
+    pub rate: Uint128,
+    pub last_updated_base: u64,
+    pub last_updated_quote: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// only applied when converting a legacy config
+    pub price_validity_period: u64,
+}